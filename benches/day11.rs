@@ -0,0 +1,44 @@
+use aoc2020::y2020::day11::{input_generator, SeatRule, Simulation, WaitingArea};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// Square seat map with roughly one floor tile in five, the same every run
+fn seat_map(size: usize) -> WaitingArea {
+    let mut state: u32 = 2020;
+    let mut input = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        for _ in 0..size {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            input.push(if (state >> 16).is_multiple_of(5) {
+                '.'
+            } else {
+                'L'
+            });
+        }
+        input.push('\n');
+    }
+    input_generator(&input).unwrap()
+}
+
+fn step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day11_step");
+    group.sample_size(20);
+    for &size in &[100, 1000, 3000] {
+        let area = seat_map(size);
+        // A few generations in, so there's a mix of occupied and empty seats
+        let mut sim = Simulation::new(area, SeatRule::part2());
+        for _ in 0..3 {
+            sim.step();
+        }
+        group.bench_with_input(BenchmarkId::new("sequential", size), &size, |b, _| {
+            b.iter(|| sim.step())
+        });
+        #[cfg(feature = "parallel")]
+        group.bench_with_input(BenchmarkId::new("parallel", size), &size, |b, _| {
+            b.iter(|| sim.par_step())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, step);
+criterion_main!(benches);
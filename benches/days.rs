@@ -0,0 +1,40 @@
+// Every day's generator and parts against the real inputs in input/YEAR.
+// Criterion keeps its estimates as JSON under target/criterion, so runs can be
+// compared with `cargo bench --bench days -- --save-baseline before` and then
+// `--baseline before` after a change.
+use aoc2020::solver::{solvers, Part};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::fs;
+
+fn days(c: &mut Criterion) {
+    for s in solvers() {
+        let path = format!("input/{}/day{}.txt", s.year(), s.day());
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!(
+                    "skipping {} day {}, could not read {}: {}",
+                    s.year(),
+                    s.day(),
+                    path,
+                    e
+                );
+                continue;
+            }
+        };
+        let mut group = c.benchmark_group(format!("{}_day{}", s.year(), s.day()));
+        group.bench_function("generate", |b| {
+            b.iter(|| s.generate(black_box(&input)).unwrap())
+        });
+        let generated = s.generate(&input).unwrap();
+        for &part in &Part::BOTH {
+            group.bench_function(format!("part{}", part), |b| {
+                b.iter(|| generated(black_box(part)).unwrap())
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
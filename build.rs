@@ -0,0 +1,55 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Numbered entries of dir whose names start with prefix, like day11 or 2020
+fn numbered(dir: &Path, prefix: &str) -> Vec<(u32, fs::DirEntry)> {
+    println!("cargo:rerun-if-changed={}", dir.display());
+    let mut found = Vec::new();
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(n) = name.strip_prefix(prefix).and_then(|n| n.parse().ok()) {
+            found.push((n, entry));
+        }
+    }
+    found
+}
+
+// Lists every example fixture, examples/YEAR/dayN/NAME.txt, as an example!
+// macro call so tests/examples.rs gets one test per file
+fn main() {
+    let mut fixtures = Vec::new();
+    for (year, year_dir) in numbered(Path::new("examples"), "") {
+        for (day, day_dir) in numbered(&year_dir.path(), "day") {
+            println!("cargo:rerun-if-changed={}", day_dir.path().display());
+            for file in fs::read_dir(day_dir.path()).into_iter().flatten().flatten() {
+                let path = file.path();
+                if path.extension().is_some_and(|e| e == "txt") {
+                    let stem = path.file_stem().unwrap().to_string_lossy().into_owned();
+                    fixtures.push((year, day, stem));
+                }
+            }
+        }
+    }
+    fixtures.sort();
+
+    let mut out = String::new();
+    for (year, day, name) in fixtures {
+        let ident: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        out += &format!(
+            "example!(y{}_day{}_{}, {}, {}, {:?});\n",
+            year, day, ident, year, day, name
+        );
+    }
+    let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(dest, out).unwrap();
+}
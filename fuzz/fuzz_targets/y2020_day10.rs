@@ -0,0 +1,5 @@
+#![no_main]
+use aoc2020_fuzz::check_day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| check_day(2020, 10, input));
@@ -0,0 +1,16 @@
+#![no_main]
+use aoc2020::y2020::day11::{input_generator, SeatRule, Simulation};
+use aoc2020_fuzz::STEP_LIMIT;
+use libfuzzer_sys::fuzz_target;
+
+// Runs both parts' rules itself, since the solver allows so many generations
+// that a slow-settling layout would hold up the fuzzer
+fuzz_target!(|input: &str| {
+    if let Ok(area) = input_generator(input) {
+        for rule in [SeatRule::part1(), SeatRule::part2()] {
+            let mut sim = Simulation::new(area.clone(), rule);
+            let _ = sim.run(STEP_LIMIT);
+            let _ = sim.area().occupied();
+        }
+    }
+});
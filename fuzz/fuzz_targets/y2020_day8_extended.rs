@@ -0,0 +1,24 @@
+#![no_main]
+use aoc2020::y2020::day8::{find_loop, repairs, InstructionSet, Queues, Step, VirtualMachine};
+use aoc2020_fuzz::STEP_LIMIT;
+use libfuzzer_sys::fuzz_target;
+
+// The day 8 target only covers the core instructions the solver parses, so
+// this one adds the registers and I/O of the extended set. Those programs can
+// count for a very long time before repeating a state, so they only get so
+// many steps.
+fuzz_target!(|input: &str| {
+    if let Ok(prog) = InstructionSet::extended().parse_program(input) {
+        let _ = find_loop(&prog);
+        let _ = repairs(&prog);
+        let mut vm = VirtualMachine::new();
+        let mut io = Queues::default();
+        io.input.extend(&[0, 1, -1]);
+        for _ in 0..STEP_LIMIT {
+            match vm.step_io(&prog, &mut io) {
+                Ok(Step::Running) => (),
+                _ => break,
+            }
+        }
+    }
+});
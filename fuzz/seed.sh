@@ -0,0 +1,17 @@
+#!/bin/sh
+# Seeds each target's corpus with the real input and the examples for its day,
+# then run a target with e.g. `cargo +nightly fuzz run y2020_day4`
+set -e
+cd "$(dirname "$0")"
+for target in fuzz_targets/*.rs; do
+    name=$(basename "$target" .rs)
+    year=${name%%_*}
+    year=${year#y}
+    day=$(echo "$name" | cut -d_ -f2)
+    mkdir -p "corpus/$name"
+    for seed in "../input/$year/$day.txt" "../examples/$year/$day"/*.txt; do
+        if [ -f "$seed" ]; then
+            cp "$seed" "corpus/$name/$(echo "$seed" | tr / _ | sed 's/^\.*_*//')"
+        fi
+    done
+done
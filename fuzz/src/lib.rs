@@ -0,0 +1,20 @@
+// What every fuzz target checks, whatever day it's for
+use aoc2020::parse::ParseError;
+use aoc2020::solver::{solver, Part};
+
+// Generations or steps a target runs before giving up on a day that can loop,
+// far fewer than the solvers allow so each input stays quick
+pub const STEP_LIMIT: usize = 1_000;
+
+// Any input either parses or fails with a located ParseError, and then both
+// parts finish without panicking, with or without an answer
+pub fn check_day(year: u32, day: u32, input: &str) {
+    match solver(year, day).unwrap().generate(input) {
+        Ok(generated) => {
+            for &part in &Part::BOTH {
+                let _ = generated(part);
+            }
+        }
+        Err(e) => assert!(e.is::<ParseError>(), "{}", e),
+    }
+}
@@ -0,0 +1,161 @@
+use crate::solver::Part;
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Known correct answers, stored as a small subset of TOML:
+//
+//     [day1]
+//     part1 = "121396"
+//     part2 = "73616634"
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(BTreeMap<(u32, Part), String>);
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        Self::parse_from(input, None)
+    }
+
+    // Answers for just one day, without the [dayN] table header
+    pub fn parse_day(day: u32, input: &str) -> Result<Self, String> {
+        Self::parse_from(input, Some(day))
+    }
+
+    fn parse_from(input: &str, mut day: Option<u32>) -> Result<Self, String> {
+        let mut answers = BTreeMap::new();
+        for (i, line) in input.lines().enumerate() {
+            let err = |message: &str| format!("line {}: {}", i + 1, message);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let name = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| err("expected ] after the table name"))?;
+                let d = name
+                    .strip_prefix("day")
+                    .and_then(|d| d.parse::<u32>().ok())
+                    .ok_or_else(|| err("expected a table like [day1]"))?;
+                day = Some(d);
+                continue;
+            }
+            let (key, value) = match line.find('=') {
+                Some(j) => (line[..j].trim(), line[j + 1..].trim()),
+                None => return Err(err("expected part1 = \"answer\"")),
+            };
+            let day = day.ok_or_else(|| err("answer outside of a [dayN] table"))?;
+            let part = match key {
+                "part1" => Part::One,
+                "part2" => Part::Two,
+                _ => return Err(err("expected part1 or part2")),
+            };
+            let value = match value.strip_prefix('"') {
+                Some(quoted) => quoted
+                    .strip_suffix('"')
+                    .ok_or_else(|| err("unterminated string"))?,
+                None if value.parse::<i128>().is_ok() => value,
+                None => return Err(err("expected a quoted answer or an integer")),
+            };
+            if answers.insert((day, part), value.to_string()).is_some() {
+                return Err(err("duplicate answer"));
+            }
+        }
+        Ok(Answers(answers))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, Part, &str)> {
+        self.0
+            .iter()
+            .map(|(&(day, part), answer)| (day, part, answer.as_str()))
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u32, part: Part, answer: String) {
+        self.0.insert((day, part), answer);
+    }
+
+    // How an answer compares to the recorded one
+    pub fn check(&self, day: u32, part: Part, actual: &str) -> Check {
+        match self.get(day, part) {
+            Some(expected) if expected == actual => Check::Match,
+            Some(expected) => Check::Mismatch(expected.to_string()),
+            None => Check::Unrecorded,
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut last = None;
+        for ((day, part), answer) in &self.0 {
+            if last != Some(*day) {
+                if last.is_some() {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[day{}]", day)?;
+                last = Some(*day);
+            }
+            writeln!(f, "part{} = \"{}\"", part, answer)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Check {
+    Match,
+    // Holds the expected answer
+    Mismatch(String),
+    Unrecorded,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = "# answers\n[day1]\npart1 = \"121396\"\npart2 = 73616634\n\n[day10]\npart1 = \"2482\"\n";
+        let answers = Answers::parse(input).unwrap();
+        assert_eq!(answers.get(1, Part::One), Some("121396"));
+        assert_eq!(answers.get(1, Part::Two), Some("73616634"));
+        assert_eq!(answers.get(10, Part::Two), None);
+        assert_eq!(
+            answers.to_string(),
+            "[day1]\npart1 = \"121396\"\npart2 = \"73616634\"\n\n[day10]\npart1 = \"2482\"\n"
+        );
+        assert_eq!(Answers::parse(&answers.to_string()), Ok(answers));
+
+        let day = Answers::parse_day(7, "part2 = \"126\"\n").unwrap();
+        assert_eq!(day.iter().collect::<Vec<_>>(), vec![(7, Part::Two, "126")]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Answers::parse("part1 = \"1\""),
+            Err("line 1: answer outside of a [dayN] table".to_string())
+        );
+        assert!(Answers::parse("[day1\n").is_err());
+        assert!(Answers::parse("[night1]\n").is_err());
+        assert!(Answers::parse("[day1]\npart3 = \"1\"\n").is_err());
+        assert!(Answers::parse("[day1]\npart1 = \"1\n").is_err());
+        assert!(Answers::parse("[day1]\npart1 = one\n").is_err());
+        assert!(Answers::parse("[day1]\npart1 = 1\npart1 = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let mut answers = Answers::default();
+        answers.insert(11, Part::Two, "2124".to_string());
+        assert_eq!(answers.check(11, Part::Two, "2124"), Check::Match);
+        assert_eq!(
+            answers.check(11, Part::Two, "2125"),
+            Check::Mismatch("2124".to_string())
+        );
+        assert_eq!(answers.check(11, Part::One, "1"), Check::Unrecorded);
+    }
+}
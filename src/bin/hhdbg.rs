@@ -0,0 +1,383 @@
+use aoc2020::parse::{parse_lines, ParseError};
+use aoc2020::y2020::day8::{
+    find_loop, InstructionSet, Op, Program, ProgramError, Reg, Step, VirtualMachine,
+};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+const USAGE: &str = "usage: hhdbg [--extended] <program>
+  --extended  also accept the register and I/O instructions";
+
+const HELP: &str = "\
+commands:
+  b <ip>          break when ip reaches <ip>
+  b <op>          break before executing that kind of op, e.g. b jmp
+  d               delete all breakpoints
+  s [n]           step n instructions (default 1)
+  c               continue until a breakpoint, halt, error or loop
+  r               show ip, acc and the step count, plus registers a-h once
+                  any are set
+  l [n]           list n instructions around ip (default 5)
+  p <ip>          patch instruction at <ip> (jmp <-> nop)
+  w <n>           rewind to step n
+  h               show this help
+  q               quit";
+
+#[derive(PartialEq)]
+enum Breakpoint {
+    Ip(usize),
+    Op(String),
+}
+
+enum Stop {
+    Stepped,
+    Breakpoint,
+    Halted(isize),
+    Error(ProgramError),
+    Loop,
+}
+
+struct Debugger {
+    // What the program was loaded with, so op breakpoints can be checked
+    isa: InstructionSet,
+    prog: Program,
+    vm: VirtualMachine,
+    // Machine state before each executed step, so history[n] is the state at step n
+    history: Vec<VirtualMachine>,
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl Debugger {
+    fn new(isa: InstructionSet, prog: Program) -> Self {
+        Self {
+            isa,
+            prog,
+            vm: VirtualMachine::new(),
+            history: Vec::new(),
+            breakpoints: Vec::new(),
+        }
+    }
+
+    fn at_breakpoint(&self) -> bool {
+        let op = self.prog.get(self.vm.ip).map(Op::name);
+        self.breakpoints.iter().any(|bp| match bp {
+            Breakpoint::Ip(ip) => *ip == self.vm.ip,
+            Breakpoint::Op(name) => Some(name.as_str()) == op,
+        })
+    }
+
+    fn step(&mut self) -> Stop {
+        let prev = self.vm.clone();
+        match self.vm.step(&self.prog) {
+            Ok(Step::Running) => {
+                self.history.push(prev);
+                Stop::Stepped
+            }
+            Ok(Step::Halted(acc)) => Stop::Halted(acc),
+            Err(e) => Stop::Error(e),
+        }
+    }
+
+    // Extended instructions can branch on registers, so coming back to an ip
+    // only means a loop if the registers are the same too
+    fn is_extended(&self) -> bool {
+        self.prog.iter().any(|op| matches!(op, Op::Ext(_)))
+    }
+
+    fn cont(&mut self) -> Stop {
+        let exact = self.is_extended();
+        let mut visited = HashSet::new();
+        loop {
+            let state = if exact {
+                (self.vm.ip, self.vm.acc, self.vm.regs)
+            } else {
+                (self.vm.ip, 0, Default::default())
+            };
+            if !visited.insert(state) {
+                return Stop::Loop;
+            }
+            match self.step() {
+                Stop::Stepped if self.at_breakpoint() => return Stop::Breakpoint,
+                Stop::Stepped => (),
+                stop => return stop,
+            }
+        }
+    }
+
+    fn rewind(&mut self, step: usize) -> Result<(), String> {
+        if step > self.history.len() {
+            return Err(format!("only {} steps executed", self.history.len()));
+        }
+        if step < self.history.len() {
+            self.vm = self.history[step].clone();
+            self.history.truncate(step);
+        }
+        Ok(())
+    }
+
+    fn patch(&mut self, ip: usize) -> Result<Op, String> {
+        let op = self
+            .prog
+            .get(ip)
+            .ok_or_else(|| format!("no instruction at {}", ip))?;
+        let new_op = op.flip().ok_or_else(|| format!("cannot patch {}", op))?;
+        self.prog[ip] = new_op.clone();
+        Ok(new_op)
+    }
+
+    fn regs(&self) -> String {
+        let mut out = format!(
+            "ip={} acc={} step={}",
+            self.vm.ip,
+            self.vm.acc,
+            self.history.len()
+        );
+        if self.vm.regs.iter().any(|&r| r != 0) {
+            for (i, r) in self.vm.regs.iter().enumerate() {
+                out += &format!(" {}={}", Reg::Gp(i), r);
+            }
+        }
+        out
+    }
+
+    fn list(&self, n: usize) -> String {
+        let start = self.vm.ip.saturating_sub(n / 2);
+        let end = (start + n).min(self.prog.len());
+        let mut out = String::new();
+        for i in start..end {
+            let marker = if i == self.vm.ip { "=>" } else { "  " };
+            out.push_str(&format!("{} {:5} {}\n", marker, i, self.prog[i]));
+        }
+        if self.vm.ip == self.prog.len() {
+            out.push_str(&format!("=> {:5} <end>\n", self.vm.ip));
+        }
+        out
+    }
+
+    fn describe(&self, stop: Stop) -> String {
+        match stop {
+            Stop::Stepped => self.list(1),
+            Stop::Breakpoint => format!("breakpoint\n{}", self.list(1)),
+            Stop::Halted(acc) => format!("program terminated with acc={}", acc),
+            Stop::Error(e) => format!("error: {}", e),
+            Stop::Loop => {
+                let mut out = "infinite loop detected\n".to_string();
+                let looped = find_loop(&self.prog).map(|looped| looped.cycle);
+                if let (false, Ok(Some(cycle))) = (self.is_extended(), looped) {
+                    out += &format!("from the start it loops through {}\n", cycle);
+                }
+                out + &self.list(1)
+            }
+        }
+    }
+
+    fn exec(&mut self, line: &str) -> Option<String> {
+        let mut words = line.split_whitespace();
+        let cmd = words.next().unwrap_or("");
+        let arg = words.next();
+        let num = arg.map(|a| a.parse::<usize>());
+        let out = match (cmd, num) {
+            ("", _) => String::new(),
+            ("q", _) => return None,
+            ("h", _) => HELP.to_string(),
+            ("b", Some(Ok(ip))) => {
+                self.breakpoints.push(Breakpoint::Ip(ip));
+                format!("breakpoint at ip {}", ip)
+            }
+            ("b", Some(Err(_))) => {
+                let name = arg.unwrap();
+                let names = self.isa.names();
+                if names.contains(&name) {
+                    self.breakpoints.push(Breakpoint::Op(name.to_string()));
+                    format!("breakpoint on {}", name)
+                } else {
+                    format!(
+                        "unknown op {:?}, expected one of {}",
+                        name,
+                        names.join(", ")
+                    )
+                }
+            }
+            ("d", _) => {
+                self.breakpoints.clear();
+                "breakpoints cleared".to_string()
+            }
+            ("s", None) => {
+                let stop = self.step();
+                self.describe(stop)
+            }
+            ("s", Some(Ok(n))) => {
+                let mut stop = Stop::Stepped;
+                for _ in 0..n {
+                    stop = self.step();
+                    if !matches!(stop, Stop::Stepped) {
+                        break;
+                    }
+                }
+                self.describe(stop)
+            }
+            ("c", _) => {
+                let stop = self.cont();
+                self.describe(stop)
+            }
+            ("r", _) => self.regs(),
+            ("l", None) => self.list(5),
+            ("l", Some(Ok(n))) => self.list(n),
+            ("p", Some(Ok(ip))) => match self.patch(ip) {
+                Ok(op) => format!("{:5} {}", ip, op),
+                Err(e) => e,
+            },
+            ("w", Some(Ok(n))) => match self.rewind(n) {
+                Ok(()) => self.regs(),
+                Err(e) => e,
+            },
+            _ => format!("invalid command {:?}, try h", line.trim()),
+        };
+        Some(out)
+    }
+}
+
+// Parses a program with isa, with errors located the way day 8's are
+fn load(isa: &InstructionSet, input: &str) -> Result<Program, ParseError> {
+    parse_lines(8, input, |line| isa.parse_line(line))
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let isa = match args.iter().position(|a| a == "--extended") {
+        Some(i) => {
+            args.remove(i);
+            InstructionSet::extended()
+        }
+        None => InstructionSet::new(),
+    };
+    let path = match args.as_slice() {
+        [path] => path.clone(),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+    let input = match fs::read_to_string(&path) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("could not read {}: {}", path, e);
+            process::exit(1);
+        }
+    };
+    let prog = match load(&isa, &input) {
+        Ok(prog) => prog,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            process::exit(1);
+        }
+    };
+    let mut debugger = Debugger::new(isa, prog);
+    println!("loaded {} instructions, h for help", debugger.prog.len());
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("(hhdbg) ");
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        match debugger.exec(&line) {
+            Some(out) if out.is_empty() => (),
+            Some(out) => println!("{}", out.trim_end()),
+            None => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    fn core(input: &str) -> Debugger {
+        let isa = InstructionSet::new();
+        let prog = load(&isa, input).unwrap();
+        Debugger::new(isa, prog)
+    }
+
+    #[test]
+    fn test_continue_detects_loop() {
+        let mut dbg = core(INPUT);
+        assert!(matches!(dbg.cont(), Stop::Loop));
+        assert_eq!(dbg.vm.ip, 1);
+        assert_eq!(dbg.vm.acc, 5);
+        assert_eq!(
+            dbg.describe(Stop::Loop),
+            "infinite loop detected\n\
+             from the start it loops through 1 -> 2 -> 6 -> 7 -> 3 -> 4 -> 1\n\
+             =>     1 acc +1\n"
+        );
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut dbg = core(INPUT);
+        dbg.exec("b 6");
+        assert!(matches!(dbg.cont(), Stop::Breakpoint));
+        assert_eq!(dbg.vm.ip, 6);
+        dbg.exec("d");
+        assert_eq!(
+            dbg.exec("b jpm").unwrap(),
+            "unknown op \"jpm\", expected one of acc, jmp, nop"
+        );
+        assert!(dbg.breakpoints.is_empty());
+        dbg.exec("b jmp");
+        assert!(matches!(dbg.cont(), Stop::Breakpoint));
+        assert_eq!(dbg.vm.ip, 7);
+    }
+
+    #[test]
+    fn test_patch_and_rewind() {
+        let mut dbg = core(INPUT);
+        dbg.exec("s 5");
+        assert_eq!(dbg.regs(), "ip=3 acc=2 step=5");
+        dbg.exec("w 2");
+        assert_eq!(dbg.regs(), "ip=2 acc=1 step=2");
+        assert!(dbg.patch(1).is_err());
+        assert_eq!(dbg.patch(7), Ok(Op::Nop(-4)));
+        assert!(matches!(dbg.cont(), Stop::Halted(8)));
+
+        dbg.vm.regs[1] = -3;
+        assert_eq!(
+            dbg.regs(),
+            "ip=9 acc=8 step=6 a=0 b=-3 c=0 d=0 e=0 f=0 g=0 h=0"
+        );
+    }
+
+    #[test]
+    fn test_extended() {
+        let program = "set a +3\nadd acc a\nadd a -1\njnz a -2";
+        assert_eq!(load(&InstructionSet::new(), program).unwrap_err().line, 1);
+        let isa = InstructionSet::extended();
+        let prog = load(&isa, program).unwrap();
+        let mut dbg = Debugger::new(isa, prog);
+        assert_eq!(dbg.exec("b add").unwrap(), "breakpoint on add");
+        assert!(matches!(dbg.cont(), Stop::Breakpoint));
+        assert_eq!(dbg.vm.ip, 1);
+        dbg.exec("d");
+        assert!(matches!(dbg.cont(), Stop::Halted(6)));
+
+        // Only a repeated state is a loop, and the day 8 cycle isn't shown
+        let isa = InstructionSet::extended();
+        let prog = load(&isa, "set a +1\njnz a +0").unwrap();
+        let mut dbg = Debugger::new(isa, prog);
+        assert_eq!(
+            dbg.exec("c").unwrap(),
+            "infinite loop detected\n=>     1 jnz a +0\n"
+        );
+        assert!(dbg.exec("b in").unwrap().starts_with("breakpoint"));
+        assert!(core(INPUT).exec("b add").unwrap().starts_with("unknown op"));
+    }
+}
@@ -0,0 +1,129 @@
+use aoc2020::y2020::day11::{
+    animate, export_ppm, input_generator, SeatRule, Simulation, MAX_GENERATIONS,
+};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str = "\
+usage: seating <input> [options]
+  --part2         use the part 2 rules (line of sight, tolerate 4)
+  --rule <rule>   use a B/S rule instead, e.g. B3/S23
+  --torus         wrap around the edges
+  --delay <ms>    delay between frames (default 100)
+  --ppm <dir>     write every generation to <dir> as PPM frames
+  --scale <n>     pixels per seat in PPM frames (default 4)
+  --max <n>       stop after n generations";
+
+struct Options {
+    input: String,
+    rule: SeatRule,
+    delay: Duration,
+    ppm: Option<PathBuf>,
+    scale: usize,
+    max: usize,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut input = None;
+    let mut rule = SeatRule::part1();
+    let mut torus = false;
+    let mut delay = Duration::from_millis(100);
+    let mut ppm = None;
+    let mut scale = 4;
+    let mut max = MAX_GENERATIONS;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        let number = |v: &String| {
+            v.parse::<usize>()
+                .map_err(|e| format!("{} {}: {}", arg, v, e))
+        };
+        match arg.as_str() {
+            "--part2" => rule = SeatRule::part2(),
+            "--rule" => rule = value()?.parse()?,
+            "--torus" => torus = true,
+            "--delay" => delay = Duration::from_millis(number(value()?)? as u64),
+            "--ppm" => ppm = Some(PathBuf::from(value()?)),
+            "--scale" => scale = number(value()?)?,
+            "--max" => max = number(value()?)?,
+            a if a.starts_with("--") => return Err(format!("unknown option {}", a)),
+            a if input.is_none() => input = Some(a.to_string()),
+            a => return Err(format!("unexpected argument {}", a)),
+        }
+    }
+    if torus {
+        rule = rule.toroidal();
+    }
+    Ok(Options {
+        input: input.ok_or("missing input")?,
+        rule,
+        delay,
+        ppm,
+        scale,
+        max,
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let opts = match parse_args(&args) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    let input = match fs::read_to_string(&opts.input) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("could not read {}: {}", opts.input, e);
+            process::exit(1);
+        }
+    };
+    let area = match input_generator(&input) {
+        Ok(area) => area,
+        Err(e) => {
+            eprintln!("{}: {}", opts.input, e);
+            process::exit(1);
+        }
+    };
+    let mut sim = Simulation::new(area, opts.rule);
+    let result = match &opts.ppm {
+        Some(dir) => export_ppm(&mut sim, dir, opts.scale, opts.max)
+            .map(|frames| println!("wrote {} frames to {}", frames, dir.display())),
+        None => animate(&mut sim, &mut io::stdout(), opts.delay, opts.max).map(|_| ()),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let opts = parse_args(&args("in.txt --rule B3/S23 --torus --ppm out --max 50")).unwrap();
+        assert_eq!(opts.input, "in.txt");
+        assert_eq!(opts.rule, "B3/S23".parse::<SeatRule>().unwrap().toroidal());
+        assert_eq!(opts.ppm, Some(PathBuf::from("out")));
+        assert_eq!(opts.max, 50);
+        assert_eq!(opts.scale, 4);
+
+        assert!(parse_args(&args("--part2")).is_err());
+        assert!(parse_args(&args("in.txt --delay")).is_err());
+        assert!(parse_args(&args("in.txt --scale x")).is_err());
+        assert!(parse_args(&args("in.txt --bogus")).is_err());
+    }
+}
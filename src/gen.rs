@@ -0,0 +1,99 @@
+// Synthetic puzzle inputs of any size, for stressing the solvers on much more
+// than the real inputs. Each one is built to have known answers, so a sample
+// doubles as a test oracle.
+use crate::answers::Answers;
+use crate::y2020;
+use std::ops::Range;
+
+pub struct Sample {
+    pub input: String,
+    // Both parts, for just the day the sample is for
+    pub answers: Answers,
+}
+
+// A sample for the puzzle, where size is roughly how many lines, entries or
+// rows to make. The same seed always gives the same sample. None if the
+// puzzle has no generator.
+pub fn sample(year: u32, day: u32, size: usize, seed: u64) -> Option<Sample> {
+    let mut rng = Rng::new(seed);
+    match year {
+        2020 => y2020::gen::sample(day, size, &mut rng),
+        _ => None,
+    }
+}
+
+// SplitMix64, which is plenty for making inputs and keeps them the same on
+// every platform
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform over a range that mustn't be empty. The modulo bias is far too
+    // small to matter here.
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as usize
+    }
+
+    pub fn signed(&mut self, range: Range<isize>) -> isize {
+        let width = (range.end - range.start) as usize;
+        range.start + self.range(0..width) as isize
+    }
+
+    // True with the given chance out of 100
+    pub fn percent(&mut self, chance: usize) -> bool {
+        self.range(0..100) < chance
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let mut again = Rng::new(7);
+        let first: Vec<u64> = (0..5).map(|_| rng.next_u64()).collect();
+        assert_eq!(first, (0..5).map(|_| again.next_u64()).collect::<Vec<_>>());
+        assert_ne!(first[0], Rng::new(8).next_u64());
+
+        for _ in 0..1000 {
+            assert!((3..7).contains(&rng.range(3..7)));
+            assert!((-2..2).contains(&rng.signed(-2..2)));
+        }
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_unknown_puzzle() {
+        assert!(sample(2019, 1, 10, 0).is_none());
+        assert!(sample(2020, 12, 10, 0).is_none());
+        assert!(sample(2020, 1, 10, 0).is_some());
+    }
+}
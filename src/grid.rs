@@ -0,0 +1,467 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+// The eight compass directions as (dy, dx), starting top-left and going cw
+pub const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub fn new(row: usize, col: usize) -> Self {
+        Point { row, col }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GridError {
+    // An empty first line, which would make a grid with rows but no columns
+    Empty {
+        line: usize,
+    },
+    // A line whose length doesn't match the first line's
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    // A character the cell mapping rejected
+    Cell {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Empty { line } => write!(f, "Line {} is empty.", line),
+            GridError::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {} is {} characters long, expected {}.",
+                line, found, expected
+            ),
+            GridError::Cell {
+                line,
+                column,
+                message,
+            } => write!(f, "Line {}, column {}: {}", line, column, message),
+        }
+    }
+}
+impl Error for GridError {}
+
+// Cells stored row-major. Lines and columns in errors count from 1.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    // None unless cells has exactly width * height elements, or for rows with
+    // no columns
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        if cells.len() == width * height && (width > 0 || height == 0) {
+            Some(Grid {
+                width,
+                height,
+                cells,
+            })
+        } else {
+            None
+        }
+    }
+
+    // One row per line, with each character mapped to a cell
+    pub fn parse<F, E>(input: &str, mut cell: F) -> Result<Self, GridError>
+    where
+        F: FnMut(char) -> Result<T, E>,
+        E: fmt::Display,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(input.len());
+        for (i, line) in input.lines().enumerate() {
+            let len = line.chars().count();
+            match width {
+                None if len == 0 => return Err(GridError::Empty { line: i + 1 }),
+                None => width = Some(len),
+                Some(w) if w != len => {
+                    return Err(GridError::Ragged {
+                        line: i + 1,
+                        expected: w,
+                        found: len,
+                    })
+                }
+                _ => (),
+            }
+            for (j, ch) in line.chars().enumerate() {
+                cells.push(cell(ch).map_err(|e| GridError::Cell {
+                    line: i + 1,
+                    column: j + 1,
+                    message: e.to_string(),
+                })?);
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // Cells in row-major order, so point p is at index_of(p)
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn index_of(&self, p: Point) -> usize {
+        p.row * self.width + p.col
+    }
+
+    pub fn point_of(&self, index: usize) -> Point {
+        Point::new(index / self.width, index % self.width)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.row < self.height && p.col < self.width
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.contains(p) {
+            Some(&self.cells[self.index_of(p)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if self.contains(p) {
+            let i = self.index_of(p);
+            Some(&mut self.cells[i])
+        } else {
+            None
+        }
+    }
+
+    // Treats the grid as repeating forever in every direction, or None if
+    // there's nothing to repeat
+    pub fn get_wrapping(&self, row: isize, col: isize) -> Option<&T> {
+        Some(&self[self.wrap(row, col)?])
+    }
+
+    fn wrap(&self, row: isize, col: isize) -> Option<Point> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let h = self.height as isize;
+        let w = self.width as isize;
+        Some(Point::new(
+            row.rem_euclid(h) as usize,
+            col.rem_euclid(w) as usize,
+        ))
+    }
+
+    // dist steps from p in direction (dy, dx), or None past an edge
+    pub fn offset(&self, p: Point, (dy, dx): (isize, isize), dist: usize) -> Option<Point> {
+        let d = dist as isize;
+        let row = p.row as isize + dy * d;
+        let col = p.col as isize + dx * d;
+        if row < 0 || col < 0 {
+            return None;
+        }
+        let q = Point::new(row as usize, col as usize);
+        if self.contains(q) {
+            Some(q)
+        } else {
+            None
+        }
+    }
+
+    // dist steps from p in direction (dy, dx), wrapping around the edges, or
+    // None on an empty grid
+    pub fn offset_wrapping(
+        &self,
+        p: Point,
+        (dy, dx): (isize, isize),
+        dist: usize,
+    ) -> Option<Point> {
+        let d = dist as isize;
+        self.wrap(p.row as isize + dy * d, p.col as isize + dx * d)
+    }
+
+    // The up to eight points around p, in DIRECTIONS order
+    pub fn neighbours(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS
+            .iter()
+            .filter_map(move |&dir| self.offset(p, dir, 1))
+    }
+
+    // The eight points around p on a torus, in DIRECTIONS order. On grids
+    // narrower than three cells some of them are the same point, and an empty
+    // grid has none.
+    pub fn neighbours_wrapping(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS
+            .iter()
+            .filter_map(move |&dir| self.offset_wrapping(p, dir, 1))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, and a zero-width grid has no cells anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "Column {} is out of bounds.", col);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Point::new(row, col)))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    // Builds a new grid where cell (row, col) comes from at(row, col)
+    fn rebuild<F: Fn(usize, usize) -> Point>(&self, width: usize, height: usize, at: F) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for row in 0..height {
+            for col in 0..width {
+                cells.push(self[at(row, col)].clone());
+            }
+        }
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // Quarter turn clockwise
+    pub fn rotate(&self) -> Self {
+        let h = self.height;
+        self.rebuild(h, self.width, |row, col| Point::new(h - 1 - col, row))
+    }
+
+    // Mirror left to right
+    pub fn flip_horizontal(&self) -> Self {
+        let w = self.width;
+        self.rebuild(w, self.height, |row, col| Point::new(row, w - 1 - col))
+    }
+
+    // Mirror top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        let h = self.height;
+        self.rebuild(self.width, h, |row, col| Point::new(h - 1 - row, col))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        assert!(
+            self.contains(p),
+            "Point ({}, {}) is out of bounds for a {}x{} grid.",
+            p.row,
+            p.col,
+            self.width,
+            self.height
+        );
+        &self.cells[self.index_of(p)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        assert!(
+            self.contains(p),
+            "Point ({}, {}) is out of bounds for a {}x{} grid.",
+            p.row,
+            p.col,
+            self.width,
+            self.height
+        );
+        let i = self.index_of(p);
+        &mut self.cells[i]
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self[Point::new(row, col)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self[Point::new(row, col)]
+    }
+}
+
+// One line per row, each cell written with its own Display
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10).ok_or("not a digit")).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid[Point::new(0, 2)], 3);
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        let ragged = Grid::parse("12\n345\n", |c| c.to_digit(10).ok_or("not a digit"));
+        assert_eq!(
+            ragged,
+            Err(GridError::Ragged {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+        let bad = Grid::parse("12\n3x\n", |c| c.to_digit(10).ok_or("not a digit"));
+        assert_eq!(
+            bad.unwrap_err().to_string(),
+            "Line 2, column 2: not a digit"
+        );
+        assert!(digits("").is_empty());
+        let empty = Grid::parse("\n", |c| c.to_digit(10).ok_or("not a digit"));
+        assert_eq!(empty, Err(GridError::Empty { line: 1 }));
+        assert_eq!(Grid::<u32>::from_vec(0, 2, vec![]), None);
+        assert!(Grid::<u32>::from_vec(0, 0, vec![]).is_some());
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = digits("123\n456\n");
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.points().nth(4), Some(Point::new(1, 1)));
+        assert_eq!(grid.point_of(5), Point::new(1, 2));
+        assert_eq!(grid.index_of(Point::new(1, 2)), 5);
+        assert_eq!(grid.map(|d| d * 2).row(0), [2, 4, 6]);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789\n");
+        let around = |p| -> Vec<u32> { grid.neighbours(p).map(|q| grid[q]).collect() };
+        assert_eq!(around(Point::new(1, 1)), vec![1, 2, 3, 6, 9, 8, 7, 4]);
+        assert_eq!(around(Point::new(0, 0)), vec![2, 5, 4]);
+        let wrapped: Vec<u32> = grid
+            .neighbours_wrapping(Point::new(0, 0))
+            .map(|q| grid[q])
+            .collect();
+        assert_eq!(wrapped, vec![9, 7, 8, 2, 5, 4, 6, 3]);
+        assert_eq!(grid.get_wrapping(-1, 4), Some(&8));
+        assert_eq!(
+            grid.offset(Point::new(0, 0), (1, 1), 2),
+            Some(Point::new(2, 2))
+        );
+        assert_eq!(grid.offset(Point::new(0, 0), (1, 1), 3), None);
+
+        // There's nothing to wrap around to on an empty grid
+        let empty = digits("");
+        assert_eq!(empty.get_wrapping(0, 0), None);
+        assert_eq!(empty.offset_wrapping(Point::new(0, 0), (1, 1), 1), None);
+        assert_eq!(empty.neighbours_wrapping(Point::new(0, 0)).count(), 0);
+        assert_eq!(Grid::filled(0, 3, 0).get_wrapping(1, 0), None);
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = digits("123\n456\n");
+        assert_eq!(grid.rotate().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotate().rotate().rotate().rotate(), grid);
+        assert_eq!(grid.flip_horizontal().to_string(), "321\n654\n");
+        assert_eq!(grid.flip_vertical().to_string(), "456\n123\n");
+        assert_eq!(grid[(1, 1)], 5);
+        let mut grid = Grid::filled(2, 2, 0);
+        grid[(1, 0)] = 7;
+        assert_eq!(grid.cells(), [0, 0, 7, 0]);
+    }
+}
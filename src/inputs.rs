@@ -0,0 +1,258 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+// The site asks tools not to hit it more often than this
+pub const INTERVAL: Duration = Duration::from_secs(5);
+pub(crate) const USER_AGENT: &str =
+    concat!("aoc2020/", env!("CARGO_PKG_VERSION"), " input fetcher");
+
+// The session cookie from $AOC_SESSION, or else from ~/.config/aoc/session
+pub fn session() -> Option<String> {
+    let from_env = env::var(SESSION_VAR).ok();
+    let from_file = || {
+        let home = env::var_os("HOME")?;
+        fs::read_to_string(PathBuf::from(home).join(".config/aoc/session")).ok()
+    };
+    from_env
+        .or_else(from_file)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+// Why a request to the site failed, whether fetching or submitting
+#[derive(Debug)]
+pub enum HttpError {
+    NoSession,
+    Status { url: String, status: u16 },
+    // The request failed before there was a response
+    Request(String),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::NoSession => write!(
+                f,
+                "No session cookie, set {} or write it to ~/.config/aoc/session.",
+                SESSION_VAR
+            ),
+            HttpError::Status { url, status: 404 } => {
+                write!(f, "{} was not found, is the puzzle unlocked yet?", url)
+            }
+            HttpError::Status { url, status } => write!(f, "{} returned HTTP {}.", url, status),
+            HttpError::Request(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for HttpError {}
+
+// The body of the response to a request sent to url
+pub(crate) fn read_response(
+    url: String,
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<String, HttpError> {
+    match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| HttpError::Request(format!("{}: {}", url, e))),
+        Err(ureq::Error::Status(status, _)) => Err(HttpError::Status { url, status }),
+        Err(e) => Err(HttpError::Request(e.to_string())),
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Http(HttpError),
+    Io { path: PathBuf, error: io::Error },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Http(e) => write!(f, "{}", e),
+            InputError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+impl From<HttpError> for InputError {
+    fn from(e: HttpError) -> Self {
+        InputError::Http(e)
+    }
+}
+
+// Puzzle inputs cached under root as {year}/day{N}.txt. Anything missing is
+// downloaded, at most once per interval, and never downloaded again.
+pub struct Inputs {
+    pub base_url: String,
+    pub root: PathBuf,
+    pub session: Option<String>,
+    pub interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Inputs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Inputs {
+            base_url: BASE_URL.to_string(),
+            root: root.into(),
+            session: session(),
+            interval: INTERVAL,
+            last_request: None,
+        }
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.root
+            .join(year.to_string())
+            .join(format!("day{}.txt", day))
+    }
+
+    pub fn is_cached(&self, year: u32, day: u32) -> bool {
+        self.path(year, day).is_file()
+    }
+
+    // The cached input, without going to the network
+    pub fn read(&self, year: u32, day: u32) -> Result<String, InputError> {
+        let path = self.path(year, day);
+        fs::read_to_string(&path).map_err(|error| InputError::Io { path, error })
+    }
+
+    // The cached input, downloading it first if needed
+    pub fn get(&mut self, year: u32, day: u32) -> Result<String, InputError> {
+        if self.is_cached(year, day) {
+            return self.read(year, day);
+        }
+        let input = self.fetch(year, day)?;
+        let path = self.path(year, day);
+        let io_err = |error| InputError::Io {
+            path: path.clone(),
+            error,
+        };
+        fs::create_dir_all(path.parent().unwrap()).map_err(io_err)?;
+        // Written under another name first so an interrupted download is
+        // never mistaken for a cached input
+        let partial = path.with_extension("txt.part");
+        fs::write(&partial, &input).map_err(io_err)?;
+        fs::rename(&partial, &path).map_err(io_err)?;
+        Ok(input)
+    }
+
+    fn fetch(&mut self, year: u32, day: u32) -> Result<String, InputError> {
+        let session = self.session.as_ref().ok_or(HttpError::NoSession)?;
+        if let Some(last) = self.last_request {
+            if let Some(wait) = self.interval.checked_sub(last.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+        self.last_request = Some(Instant::now());
+
+        let url = format!(
+            "{}/{}/day/{}/input",
+            self.base_url.trim_end_matches('/'),
+            year,
+            day
+        );
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .call();
+        Ok(read_response(url, response)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::Stub;
+    use std::path::Path;
+    use std::process;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc2020-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn inputs(stub: &Stub, root: &Path) -> Inputs {
+        Inputs {
+            base_url: stub.url.clone(),
+            root: root.to_path_buf(),
+            session: Some("abc123".to_string()),
+            interval: Duration::from_millis(200),
+            last_request: None,
+        }
+    }
+
+    #[test]
+    fn test_download_and_cache() {
+        let stub = Stub::start(|request| match request.path.as_str() {
+            "/2020/day/1/input" => (200, "1721\n979\n".to_string()),
+            "/2020/day/2/input" => (200, "1-3 a: abcde\n".to_string()),
+            _ => (404, "Not found".to_string()),
+        });
+        let root = temp_dir("inputs");
+        let mut inputs = inputs(&stub, &root);
+
+        assert!(!inputs.is_cached(2020, 1));
+        let start = Instant::now();
+        assert_eq!(inputs.get(2020, 1).unwrap(), "1721\n979\n");
+        assert_eq!(
+            fs::read_to_string(root.join("2020/day1.txt")).unwrap(),
+            "1721\n979\n"
+        );
+        assert_eq!(inputs.get(2020, 1).unwrap(), "1721\n979\n");
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].header("Cookie"), Some("session=abc123"));
+        assert!(requests[0]
+            .header("User-Agent")
+            .unwrap()
+            .starts_with("aoc2020/"));
+
+        // The second download waits out the interval after the first
+        assert_eq!(inputs.get(2020, 2).unwrap(), "1-3 a: abcde\n");
+        assert!(start.elapsed() >= inputs.interval);
+
+        match inputs.get(2020, 25) {
+            Err(InputError::Http(HttpError::Status { status: 404, .. })) => (),
+            other => panic!("expected a 404, got {:?}", other),
+        }
+        assert!(!inputs.is_cached(2020, 25));
+        assert_eq!(stub.requests().len(), 3);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_no_session() {
+        let stub = Stub::start(|_| (200, "1\n".to_string()));
+        let root = temp_dir("inputs-session");
+        let mut inputs = inputs(&stub, &root);
+        inputs.session = None;
+        assert!(matches!(
+            inputs.get(2020, 1),
+            Err(InputError::Http(HttpError::NoSession))
+        ));
+        assert!(stub.requests().is_empty());
+
+        // Cached inputs don't need one
+        fs::create_dir_all(root.join("2020")).unwrap();
+        fs::write(root.join("2020/day1.txt"), "2\n").unwrap();
+        assert_eq!(inputs.get(2020, 1).unwrap(), "2\n");
+        assert!(stub.requests().is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
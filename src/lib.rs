@@ -1,15 +1,18 @@
 use aoc_runner_derive::aoc_lib;
 
-pub mod day1;
-pub mod day10;
-pub mod day11;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
+#[macro_use]
+pub mod solver;
+
+pub mod answers;
+pub mod gen;
+pub mod grid;
+pub mod inputs;
+pub mod parse;
+#[cfg(test)]
+mod stub;
+pub mod submit;
+
+// Each year's puzzles. Everything above is shared between years.
+pub mod y2020;
 
 aoc_lib! { year = 2020 }
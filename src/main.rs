@@ -1,3 +1,763 @@
-use aoc_runner_derive::aoc_main;
+use aoc2020::answers::{Answers, Check};
+use aoc2020::gen;
+use aoc2020::inputs::Inputs;
+use aoc2020::solver::{solvers, years, Part, Solver};
+use aoc2020::submit::{Ledger, Submitter, Verdict};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
-aoc_main! { lib = aoc2020 }
+const USAGE: &str = "\
+usage: aoc2020 [verify|fetch|submit|gen] [options]
+  verify           check answers against the recorded ones
+  fetch            download missing inputs, using the session cookie in
+                   $AOC_SESSION or ~/.config/aoc/session
+  submit           post one part's answer, unless the ledger shows it's wrong
+  gen              make a synthetic input for one day, with its answers
+  --year <years>   years to run, e.g. 2020 or 2019-2020 (default all)
+  --day <days>     days to run, e.g. 3 or 1,4-6 (default all)
+  --part <1|2>     part to run (default both)
+  --input <file>   read the input from file, or - for stdin; needs one puzzle
+  --inputs <dir>   directory of YEAR/dayN.txt inputs (default input)
+  --base-url <url> site to fetch from and submit to
+                   (default https://adventofcode.com)
+  --answer <value> answer to submit instead of solving the part
+  --ledger <file>  every submitted answer (default answers/submissions.tsv)
+  --answers <dir>  directory of YEAR.toml recorded answers (default answers)
+  --size <n>       roughly how many lines gen makes (default 1000)
+  --seed <n>       seed for gen, the same one gives the same input (default 0)
+  --output <file>  write gen's input to file and its answers beside it as
+                   .toml, instead of to stdout and stderr; with --json,
+                   write the JSON to file instead of stdout
+  --time           time the generator and each part, shown as a table
+  --json           print results as JSON, with timings in ns if --time";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run,
+    Verify,
+    Fetch,
+    Submit,
+    Gen,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    command: Command,
+    years: Option<Vec<u32>>,
+    days: Option<Vec<u32>>,
+    parts: Vec<Part>,
+    input: Option<String>,
+    inputs: PathBuf,
+    base_url: Option<String>,
+    answer: Option<String>,
+    ledger: PathBuf,
+    answers: PathBuf,
+    size: usize,
+    seed: u64,
+    output: Option<PathBuf>,
+    time: bool,
+    json: bool,
+}
+
+// Comma separated numbers and inclusive ranges of them, what being the kind
+// of number for errors
+fn parse_list(input: &str, what: &str) -> Result<Vec<u32>, String> {
+    let number = |s: &str| {
+        s.parse::<u32>()
+            .map_err(|_| format!("Invalid {} {:?}.", what, s))
+    };
+    let mut numbers = Vec::new();
+    for item in input.split(',') {
+        match item.find('-') {
+            Some(i) => numbers.extend(number(&item[..i])?..=number(&item[i + 1..])?),
+            None => numbers.push(number(item)?),
+        }
+    }
+    Ok(numbers)
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut opts = Options {
+        command: Command::Run,
+        years: None,
+        days: None,
+        parts: Part::BOTH.to_vec(),
+        input: None,
+        inputs: PathBuf::from("input"),
+        base_url: None,
+        answer: None,
+        ledger: PathBuf::from("answers/submissions.tsv"),
+        answers: PathBuf::from("answers"),
+        size: 1000,
+        seed: 0,
+        output: None,
+        time: false,
+        json: false,
+    };
+    let mut args = args.iter().peekable();
+    let command = match args.peek().map(|a| a.as_str()) {
+        Some("verify") => Some(Command::Verify),
+        Some("fetch") => Some(Command::Fetch),
+        Some("submit") => Some(Command::Submit),
+        Some("gen") => Some(Command::Gen),
+        _ => None,
+    };
+    if let Some(command) = command {
+        opts.command = command;
+        args.next();
+    }
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--year" => opts.years = Some(parse_list(value()?, "year")?),
+            "--day" => opts.days = Some(parse_list(value()?, "day")?),
+            "--part" => opts.parts = vec![value()?.parse()?],
+            "--input" => opts.input = Some(value()?.to_string()),
+            "--inputs" => opts.inputs = PathBuf::from(value()?),
+            "--base-url" => opts.base_url = Some(value()?.to_string()),
+            "--answer" => opts.answer = Some(value()?.to_string()),
+            "--ledger" => opts.ledger = PathBuf::from(value()?),
+            "--answers" => opts.answers = PathBuf::from(value()?),
+            "--size" => {
+                let size = value()?;
+                opts.size = size
+                    .parse()
+                    .map_err(|_| format!("Invalid size {:?}.", size))?
+            }
+            "--seed" => {
+                let seed = value()?;
+                opts.seed = seed
+                    .parse()
+                    .map_err(|_| format!("Invalid seed {:?}.", seed))?
+            }
+            "--output" => opts.output = Some(PathBuf::from(value()?)),
+            "--time" => opts.time = true,
+            "--json" => opts.json = true,
+            a => return Err(format!("unexpected argument {}", a)),
+        }
+    }
+    if opts.input.is_some() && opts.days.as_ref().is_none_or(|d| d.len() != 1) {
+        return Err("--input needs exactly one --day".to_string());
+    }
+    if opts.output.is_some() && opts.command != Command::Gen && !opts.json {
+        return Err("--output needs gen or --json".to_string());
+    }
+    Ok(opts)
+}
+
+struct Outcome {
+    year: u32,
+    day: u32,
+    part: Part,
+    answer: Result<String, String>,
+    // Zero unless the part was solved
+    generate: Duration,
+    solve: Duration,
+}
+
+fn read_input(opts: &Options, year: u32, day: u32) -> Result<String, String> {
+    match opts.input.as_deref() {
+        Some("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("could not read stdin: {}", e))?;
+            Ok(input)
+        }
+        Some(path) => {
+            fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path, e))
+        }
+        None => Inputs::new(&opts.inputs)
+            .read(year, day)
+            .map_err(|e| format!("could not read {}", e)),
+    }
+}
+
+// The solvers for the chosen years and days. Every year and day asked for
+// has to have at least one.
+fn selected(opts: &Options) -> Result<Vec<&'static dyn Solver>, String> {
+    let wanted = |list: &Option<Vec<u32>>, n| list.as_ref().is_none_or(|l| l.contains(&n));
+    let selected: Vec<_> = solvers()
+        .into_iter()
+        .filter(|s| wanted(&opts.years, s.year()) && wanted(&opts.days, s.day()))
+        .collect();
+    for &year in opts.years.iter().flatten() {
+        if !years().contains(&year) {
+            return Err(format!("no solvers for {}", year));
+        }
+    }
+    for &day in opts.days.iter().flatten() {
+        if !selected.iter().any(|s| s.day() == day) {
+            return Err(format!("no solver for day {}", day));
+        }
+    }
+    if opts.input.is_some() && selected.len() != 1 {
+        return Err("--input needs exactly one puzzle, pick a --year".to_string());
+    }
+    Ok(selected)
+}
+
+// Downloads every selected day's input that isn't cached yet
+fn fetch(opts: &Options) -> Result<bool, String> {
+    let mut inputs = Inputs::new(&opts.inputs);
+    if let Some(url) = &opts.base_url {
+        inputs.base_url = url.clone();
+    }
+    let mut ok = true;
+    for s in selected(opts)? {
+        let (year, day) = (s.year(), s.day());
+        let path = inputs.path(year, day);
+        if inputs.is_cached(year, day) {
+            println!("{} Day {}: already in {}", year, day, path.display());
+            continue;
+        }
+        match inputs.get(year, day) {
+            Ok(_) => println!("{} Day {}: saved to {}", year, day, path.display()),
+            Err(e) => {
+                eprintln!("{} Day {}: {}", year, day, e);
+                ok = false;
+            }
+        }
+    }
+    Ok(ok)
+}
+
+// Submits one part's answer, solving it first unless --answer gave it. Returns
+// whether it was right.
+fn submit(opts: &Options) -> Result<bool, String> {
+    let selected = selected(opts)?;
+    if selected.len() != 1 || opts.parts.len() != 1 {
+        return Err("submit needs one --year, --day and --part".to_string());
+    }
+    let (s, part) = (selected[0], opts.parts[0]);
+    let label = format!("{} Day {} - Part {}", s.year(), s.day(), part);
+    let answer = match &opts.answer {
+        Some(answer) => answer.clone(),
+        None => {
+            let input = read_input(opts, s.year(), s.day());
+            match input.and_then(|input| s.solve(part, &input).map_err(|e| e.to_string())) {
+                Ok(answer) => answer,
+                Err(e) => {
+                    eprintln!("{}: FAILED\n{}", label, e);
+                    return Ok(false);
+                }
+            }
+        }
+    };
+
+    let ledger = Ledger::load(&opts.ledger).map_err(|e| e.to_string())?;
+    let mut submitter = Submitter::new(ledger);
+    if let Some(url) = &opts.base_url {
+        submitter.base_url = url.clone();
+    }
+    match submitter.submit(s.year(), s.day(), part, &answer) {
+        Ok(verdict) => {
+            println!("{}: {} is {}", label, answer, verdict);
+            Ok(verdict == Verdict::Correct)
+        }
+        Err(e) => {
+            eprintln!("{}: {}", label, e);
+            Ok(false)
+        }
+    }
+}
+
+// Makes a synthetic input for one day along with the answers it was built to
+// have
+fn generate(opts: &Options) -> Result<(), String> {
+    let selected = selected(opts)?;
+    if selected.len() != 1 {
+        return Err("gen needs one --year and --day".to_string());
+    }
+    let (year, day) = (selected[0].year(), selected[0].day());
+    let sample = gen::sample(year, day, opts.size, opts.seed)
+        .ok_or_else(|| format!("no generator for {} day {}", year, day))?;
+    match &opts.output {
+        Some(path) => {
+            let answers = path.with_extension("toml");
+            fs::write(path, &sample.input)
+                .and_then(|_| fs::write(&answers, sample.answers.to_string()))
+                .map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+            println!(
+                "{} Day {}: wrote {} and {}",
+                year,
+                day,
+                path.display(),
+                answers.display()
+            );
+        }
+        None => {
+            print!("{}", sample.input);
+            eprint!("{}", sample.answers);
+        }
+    }
+    Ok(())
+}
+
+fn run(opts: &Options) -> Result<Vec<Outcome>, String> {
+    let mut outcomes = Vec::new();
+    for s in selected(opts)? {
+        let input = read_input(opts, s.year(), s.day());
+        for &part in &opts.parts {
+            let timed = match &input {
+                Ok(input) => s.timed(part, input).map_err(|e| e.to_string()),
+                Err(e) => Err(e.clone()),
+            };
+            let (generate, solve) = match &timed {
+                Ok(t) => (t.generate, t.solve),
+                Err(_) => Default::default(),
+            };
+            outcomes.push(Outcome {
+                year: s.year(),
+                day: s.day(),
+                part,
+                answer: timed.map(|t| t.answer),
+                generate,
+                solve,
+            });
+        }
+    }
+    Ok(outcomes)
+}
+
+// Each outcome's answer checked against the recorded ones for its year.
+// Solvers that failed have no answer to check.
+fn verify(outcomes: &[Outcome], answers: &BTreeMap<u32, Answers>) -> Vec<Option<Check>> {
+    outcomes
+        .iter()
+        .map(|o| {
+            let answer = o.answer.as_ref().ok()?;
+            Some(match answers.get(&o.year) {
+                Some(answers) => answers.check(o.day, o.part, answer),
+                None => Check::Unrecorded,
+            })
+        })
+        .collect()
+}
+
+fn load_answers(path: &Path) -> Result<Answers, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    Answers::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// The recorded answers for every year that has a solver selected
+fn load_all_answers(opts: &Options) -> Result<BTreeMap<u32, Answers>, String> {
+    let mut years: Vec<u32> = selected(opts)?.iter().map(|s| s.year()).collect();
+    years.dedup();
+    years
+        .into_iter()
+        .map(|year| {
+            let path = opts.answers.join(format!("{}.toml", year));
+            Ok((year, load_answers(&path)?))
+        })
+        .collect()
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// checks, when given, has the verify result for each outcome
+fn to_json(outcomes: &[Outcome], checks: Option<&[Option<Check>]>, time: bool) -> String {
+    let items: Vec<String> = outcomes
+        .iter()
+        .enumerate()
+        .map(|(i, o)| {
+            let mut item = format!(
+                "\"year\": {}, \"day\": {}, \"part\": {}",
+                o.year, o.day, o.part
+            );
+            match &o.answer {
+                Ok(answer) => item += &format!(", \"answer\": {}", json_string(answer)),
+                Err(e) => item += &format!(", \"error\": {}", json_string(e)),
+            }
+            match checks.map(|c| &c[i]) {
+                Some(Some(Check::Match)) => item += ", \"status\": \"ok\"",
+                Some(Some(Check::Mismatch(expected))) => {
+                    item += &format!(
+                        ", \"status\": \"mismatch\", \"expected\": {}",
+                        json_string(expected)
+                    )
+                }
+                Some(Some(Check::Unrecorded)) => item += ", \"status\": \"unrecorded\"",
+                Some(None) => item += ", \"status\": \"failed\"",
+                None => (),
+            }
+            if time {
+                item += &format!(
+                    ", \"generate_ns\": {}, \"solve_ns\": {}",
+                    o.generate.as_nanos(),
+                    o.solve.as_nanos()
+                );
+            }
+            format!("  {{{}}}", item)
+        })
+        .collect();
+    format!("[\n{}\n]", items.join(",\n"))
+}
+
+// The JSON goes to --output when given, or stdout otherwise
+fn write_json(opts: &Options, json: &str) -> Result<(), String> {
+    match &opts.output {
+        Some(path) => fs::write(path, format!("{}\n", json))
+            .map_err(|e| format!("could not write {}: {}", path.display(), e)),
+        None => {
+            println!("{}", json);
+            Ok(())
+        }
+    }
+}
+
+// What to show for an outcome's answer, or its error
+fn describe(outcome: &Outcome, check: Option<&Option<Check>>) -> Result<String, String> {
+    let answer = outcome.answer.as_ref().map_err(String::clone)?;
+    Ok(match check {
+        None => answer.clone(),
+        Some(Some(Check::Match)) => format!("ok ({})", answer),
+        Some(Some(Check::Mismatch(expected))) => {
+            format!("MISMATCH, expected {} but got {}", expected, answer)
+        }
+        Some(_) => format!("no recorded answer ({})", answer),
+    })
+}
+
+fn print_text(outcomes: &[Outcome], checks: Option<&[Option<Check>]>) {
+    for (i, o) in outcomes.iter().enumerate() {
+        let label = format!("{} Day {} - Part {}", o.year, o.day, o.part);
+        match describe(o, checks.map(|c| &c[i])) {
+            Ok(result) => println!("{}: {}", label, result),
+            Err(e) => eprintln!("{}: FAILED\n{}", label, e),
+        }
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    format!("{:.1?}", d)
+}
+
+// One row per part with its generator and solve times, then the totals
+fn to_table(outcomes: &[Outcome], checks: Option<&[Option<Check>]>) -> String {
+    let mut table = format!(
+        "{:>4} {:>3} {:>4} {:>10} {:>10}  {}\n",
+        "year", "day", "part", "generate", "solve", "answer"
+    );
+    for (i, o) in outcomes.iter().enumerate() {
+        let result = describe(o, checks.map(|c| &c[i])).unwrap_or_else(|_| "FAILED".to_string());
+        table += &format!(
+            "{:>4} {:>3} {:>4} {:>10} {:>10}  {}\n",
+            o.year,
+            o.day,
+            o.part,
+            format_duration(o.generate),
+            format_duration(o.solve),
+            result
+        );
+    }
+    let generate = outcomes.iter().map(|o| o.generate).sum();
+    let solve = outcomes.iter().map(|o| o.solve).sum();
+    table += &format!(
+        "{:>13} {:>10} {:>10}\n",
+        "total",
+        format_duration(generate),
+        format_duration(solve)
+    );
+    table
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let opts = match parse_args(&args) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    let answers = match opts.command {
+        Command::Verify => match load_all_answers(&opts) {
+            Ok(answers) => Some(answers),
+            Err(e) => {
+                eprintln!("{}\n{}", e, USAGE);
+                process::exit(2);
+            }
+        },
+        Command::Run => None,
+        Command::Submit => match submit(&opts) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}\n{}", e, USAGE);
+                process::exit(2);
+            }
+        },
+        Command::Gen => match generate(&opts) {
+            Ok(()) => return,
+            Err(e) => {
+                eprintln!("{}\n{}", e, USAGE);
+                process::exit(2);
+            }
+        },
+        Command::Fetch => match fetch(&opts) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}\n{}", e, USAGE);
+                process::exit(2);
+            }
+        },
+    };
+    let outcomes = match run(&opts) {
+        Ok(outcomes) => outcomes,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    let checks = answers.map(|a| verify(&outcomes, &a));
+    if opts.json {
+        let json = to_json(&outcomes, checks.as_deref(), opts.time);
+        if let Err(e) = write_json(&opts, &json) {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    } else if opts.time {
+        print!("{}", to_table(&outcomes, checks.as_deref()));
+        for o in outcomes.iter() {
+            if let Err(e) = &o.answer {
+                eprintln!("{} Day {} - Part {}: FAILED\n{}", o.year, o.day, o.part, e);
+            }
+        }
+    } else {
+        print_text(&outcomes, checks.as_deref());
+    }
+
+    let failed = outcomes.iter().any(|o| o.answer.is_err());
+    let mismatched = checks
+        .iter()
+        .flatten()
+        .any(|c| matches!(c, Some(Check::Mismatch(_))));
+    if failed || mismatched {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let opts = parse_args(&args("--year 2020 --day 1,4-6 --part 2 --json")).unwrap();
+        assert_eq!(opts.years, Some(vec![2020]));
+        assert_eq!(opts.days, Some(vec![1, 4, 5, 6]));
+        assert_eq!(opts.parts, vec![Part::Two]);
+        assert!(opts.json);
+        assert_eq!(parse_args(&[]).unwrap().parts, Part::BOTH.to_vec());
+
+        assert!(parse_args(&args("--input x.txt")).is_err());
+        assert!(parse_args(&args("--input x.txt --day 1,2")).is_err());
+        assert!(parse_args(&args("--input - --day 3")).is_ok());
+        assert!(parse_args(&args("--day x")).is_err());
+        assert!(parse_args(&args("--year 2019-x")).is_err());
+        assert!(parse_args(&args("--part 3")).is_err());
+        assert!(parse_args(&args("--bogus")).is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = env::temp_dir().join(format!("aoc2020-main-{}", process::id()));
+        fs::create_dir_all(dir.join("2020")).unwrap();
+        fs::write(
+            dir.join("2020/day1.txt"),
+            "1721\n979\n366\n299\n675\n1456\n",
+        )
+        .unwrap();
+        fs::write(dir.join("2020/day9.txt"), "x\n").unwrap();
+        let mut opts = parse_args(&args("--day 1,9")).unwrap();
+        opts.inputs = dir.clone();
+
+        let outcomes = run(&opts).unwrap();
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(outcomes[0].year, 2020);
+        assert_eq!(outcomes[0].answer, Ok("514579".to_string()));
+        assert!(outcomes[2].answer.as_ref().unwrap_err().contains("line 1"));
+        let json = to_json(&outcomes[..2], None, false);
+        assert_eq!(
+            json,
+            "[\n  {\"year\": 2020, \"day\": 1, \"part\": 1, \"answer\": \"514579\"},\n  \
+             {\"year\": 2020, \"day\": 1, \"part\": 2, \"answer\": \"241861950\"}\n]"
+        );
+        let out = dir.join("out.json");
+        let mut json_opts = parse_args(&args("--json --output out.json")).unwrap();
+        json_opts.output = Some(out.clone());
+        write_json(&json_opts, &json).unwrap();
+        assert_eq!(fs::read_to_string(&out).unwrap(), format!("{}\n", json));
+        json_opts.output = Some(dir.join("missing/out.json"));
+        assert!(write_json(&json_opts, &json)
+            .unwrap_err()
+            .starts_with("could not write"));
+        assert!(parse_args(&args("--output out.json")).is_err());
+
+        opts.days = Some(vec![2]);
+        assert!(run(&opts).unwrap()[0].answer.is_err());
+        opts.days = Some(vec![30]);
+        assert!(run(&opts).is_err());
+        opts.days = None;
+        opts.years = Some(vec![1999]);
+        assert!(run(&opts).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(
+            parse_args(&args("verify --day 11")).unwrap().command,
+            Command::Verify
+        );
+        assert!(parse_args(&args("--day 11 verify")).is_err());
+        let opts = parse_args(&args("fetch --day 3 --base-url http://localhost:1")).unwrap();
+        assert_eq!(opts.command, Command::Fetch);
+        assert_eq!(opts.base_url.as_deref(), Some("http://localhost:1"));
+
+        let opts = parse_args(&args("submit --day 3 --part 2 --answer 42")).unwrap();
+        assert_eq!(opts.command, Command::Submit);
+        assert_eq!(opts.answer.as_deref(), Some("42"));
+        assert!(submit(&parse_args(&args("submit --day 3")).unwrap()).is_err());
+        assert!(submit(&parse_args(&args("submit --part 1")).unwrap()).is_err());
+
+        let outcome = |part, answer: Result<&str, &str>| Outcome {
+            year: 2020,
+            day: 11,
+            part,
+            answer: answer.map(String::from).map_err(String::from),
+            generate: Duration::default(),
+            solve: Duration::default(),
+        };
+        let outcomes = vec![
+            outcome(Part::One, Ok("2368")),
+            outcome(Part::Two, Ok("2125")),
+            outcome(Part::Two, Err("broken")),
+        ];
+        let answers = Answers::parse("[day11]\npart1 = \"2368\"\npart2 = \"2124\"\n").unwrap();
+        let mut years = BTreeMap::new();
+        years.insert(2020, answers);
+        let checks = verify(&outcomes, &years);
+        assert_eq!(
+            checks,
+            vec![
+                Some(Check::Match),
+                Some(Check::Mismatch("2124".to_string())),
+                None
+            ]
+        );
+        let json = to_json(&outcomes, Some(&checks), false);
+        assert!(json.contains(
+            "\"day\": 11, \"part\": 2, \"answer\": \"2125\", \"status\": \"mismatch\", \"expected\": \"2124\"}"
+        ));
+        assert!(json.contains("\"error\": \"broken\", \"status\": \"failed\""));
+        assert_eq!(
+            verify(&outcomes[..1], &BTreeMap::new()),
+            vec![Some(Check::Unrecorded)]
+        );
+    }
+
+    #[test]
+    fn test_recorded_answers() {
+        assert!(load_answers(Path::new("answers/2020.toml")).is_ok());
+        assert!(load_answers(Path::new("answers/missing.toml")).is_err());
+        let opts = parse_args(&args("verify")).unwrap();
+        assert_eq!(
+            load_all_answers(&opts).unwrap().keys().collect::<Vec<_>>(),
+            vec![&2020]
+        );
+    }
+
+    #[test]
+    fn test_time() {
+        assert!(parse_args(&args("--time")).unwrap().time);
+        let outcomes = vec![
+            Outcome {
+                year: 2020,
+                day: 1,
+                part: Part::One,
+                answer: Ok("514579".to_string()),
+                generate: Duration::from_micros(1500),
+                solve: Duration::from_nanos(250),
+            },
+            Outcome {
+                year: 2020,
+                day: 9,
+                part: Part::One,
+                answer: Err("broken".to_string()),
+                generate: Duration::default(),
+                solve: Duration::default(),
+            },
+        ];
+        assert_eq!(
+            to_table(&outcomes, None),
+            "year day part   generate      solve  answer\n\
+             2020   1    1      1.5ms    250.0ns  514579\n\
+             2020   9    1      0.0ns      0.0ns  FAILED\n        \
+                     total      1.5ms    250.0ns\n"
+        );
+        assert!(to_json(&outcomes, None, true)
+            .contains("\"answer\": \"514579\", \"generate_ns\": 1500000, \"solve_ns\": 250}"));
+    }
+
+    #[test]
+    fn test_gen() {
+        let dir = env::temp_dir().join(format!("aoc2020-gen-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("day8.txt");
+        let mut opts = parse_args(&args("gen --day 8 --size 500 --seed 3")).unwrap();
+        assert_eq!(
+            (&opts.command, opts.size, opts.seed),
+            (&Command::Gen, 500, 3)
+        );
+        opts.output = Some(output.clone());
+        generate(&opts).unwrap();
+
+        // The answers it wrote are the ones the solver finds
+        let answers = load_answers(&dir.join("day8.toml")).unwrap();
+        let mut opts = parse_args(&args("--day 8")).unwrap();
+        opts.input = Some(output.to_str().unwrap().to_string());
+        let outcomes = run(&opts).unwrap();
+        let mut years = BTreeMap::new();
+        years.insert(2020, answers);
+        assert_eq!(
+            verify(&outcomes, &years),
+            vec![Some(Check::Match), Some(Check::Match)]
+        );
+
+        assert!(parse_args(&args("gen --size x")).is_err());
+        assert!(parse_args(&args("gen --seed -1")).is_err());
+        assert!(generate(&parse_args(&args("gen --day 1,2")).unwrap()).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\nd\t"), "\"a\\\"b\\\\c\\nd\\u0009\"");
+    }
+}
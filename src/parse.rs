@@ -0,0 +1,181 @@
+use crate::grid::GridError;
+use std::error::Error;
+use std::fmt;
+
+// Why one line couldn't be parsed, before it's known which day and line it
+// came from. Columns count from 1.
+#[derive(Debug, PartialEq)]
+pub struct LineError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl LineError {
+    pub fn new(column: usize, message: impl Into<String>) -> Self {
+        LineError {
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+// Where and why a day's input couldn't be parsed. Lines and columns count
+// from 1, and text is the offending line so it can be shown with the message.
+#[derive(Clone, PartialEq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub text: String,
+}
+
+impl ParseError {
+    pub fn new(
+        day: u32,
+        input: &str,
+        line: usize,
+        column: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        let text = input.lines().nth(line.saturating_sub(1)).unwrap_or("");
+        ParseError {
+            day,
+            line,
+            column,
+            message: message.into(),
+            text: text.to_string(),
+        }
+    }
+
+    pub fn from_line(day: u32, input: &str, line: usize, err: LineError) -> Self {
+        Self::new(day, input, line, err.column, err.message)
+    }
+
+    pub fn from_grid(day: u32, input: &str, err: GridError) -> Self {
+        match err {
+            GridError::Empty { line } => Self::new(day, input, line, 1, "line is empty"),
+            GridError::Ragged {
+                line,
+                expected,
+                found,
+            } => Self::new(
+                day,
+                input,
+                line,
+                expected.min(found) + 1,
+                format!("line is {} characters long, expected {}", found, expected),
+            ),
+            GridError::Cell {
+                line,
+                column,
+                message,
+            } => Self::new(day, input, line, column, message),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.message
+        )?;
+        let margin = self.line.to_string().len();
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(
+            f,
+            "{} | {}^",
+            " ".repeat(margin),
+            " ".repeat(self.column.saturating_sub(1))
+        )
+    }
+}
+
+// aoc-runner prints generator errors with {:#?}, so Debug shows the same
+// diagnostic as Display rather than the raw fields
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl Error for ParseError {}
+
+// Parses every line of input, stopping at the first one that fails
+pub fn parse_lines<T, F>(day: u32, input: &str, mut parse: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&str) -> Result<T, LineError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|e| ParseError::from_line(day, input, i + 1, e)))
+        .collect()
+}
+
+// Whitespace separated words of line along with the column each starts at
+pub fn words(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    // Every word is a subslice of line, so its offset is the pointer difference
+    let start = line.as_ptr() as usize;
+    line.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - start + 1, word))
+}
+
+// Parses word, which starts at column, as any kind of number
+pub fn number<T: std::str::FromStr>(word: &str, column: usize) -> Result<T, LineError>
+where
+    T::Err: fmt::Display,
+{
+    word.parse::<T>()
+        .map_err(|e| LineError::new(column, format!("{:?} is not a number: {}", word, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic() {
+        let input = "1\n2\nthree\n4\n";
+        let err = parse_lines(1, input, |line| number::<usize>(line, 1)).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.text, "three");
+        assert_eq!(
+            err.to_string(),
+            "day 1, line 3, column 1: \"three\" is not a number: invalid digit found in string\n\
+             3 | three\n  | ^"
+        );
+        assert_eq!(format!("{:#?}", err), err.to_string());
+        assert_eq!(
+            parse_lines(
+                1,
+                input.replace("three", "3").as_str(),
+                |l| number::<usize>(l, 1)
+            ),
+            Ok(vec![1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn test_words() {
+        let found: Vec<_> = words("  ab cd\tef").collect();
+        assert_eq!(found, vec![(3, "ab"), (6, "cd"), (9, "ef")]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let err = ParseError::from_grid(
+            11,
+            "L.\nL.L\n",
+            GridError::Ragged {
+                line: 2,
+                expected: 2,
+                found: 3,
+            },
+        );
+        assert_eq!(err.column, 3);
+        assert!(err.to_string().ends_with("2 | L.L\n  |   ^"));
+    }
+}
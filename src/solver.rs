@@ -0,0 +1,198 @@
+use crate::y2020;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Part::One => "1",
+            Part::Two => "2",
+        })
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("Invalid part {:?}.", input)),
+        }
+    }
+}
+
+// A solver that ran but came up with nothing
+#[derive(Debug, PartialEq)]
+pub struct NoAnswer;
+
+impl fmt::Display for NoAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No answer found.")
+    }
+}
+impl Error for NoAnswer {}
+
+// Anything a part can return: a plain number, an Option, or a Result
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<String, Box<dyn Error>>;
+}
+
+macro_rules! display_answer {
+    ($($t:ty),*) => {
+        $(impl IntoAnswer for $t {
+            fn into_answer(self) -> Result<String, Box<dyn Error>> {
+                Ok(self.to_string())
+            }
+        })*
+    };
+}
+
+display_answer!(usize, isize, u32, u64, u128, i32, i64, String);
+
+impl<T: IntoAnswer> IntoAnswer for Option<T> {
+    fn into_answer(self) -> Result<String, Box<dyn Error>> {
+        self.ok_or(NoAnswer)?.into_answer()
+    }
+}
+
+impl<T: IntoAnswer, E: Error + 'static> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Result<String, Box<dyn Error>> {
+        self?.into_answer()
+    }
+}
+
+// A day's parsed input, ready to solve either part as many times as needed
+pub type Generated<'a> = Box<dyn Fn(Part) -> Result<String, Box<dyn Error>> + 'a>;
+
+// One day's puzzle: parses the input and then solves either part
+pub trait Solver: Sync {
+    fn year(&self) -> u32;
+    fn day(&self) -> u32;
+    fn generate<'a>(&self, input: &'a str) -> Result<Generated<'a>, Box<dyn Error>>;
+
+    fn solve(&self, part: Part, input: &str) -> Result<String, Box<dyn Error>> {
+        self.generate(input)?(part)
+    }
+
+    // Solves part, timing the generator separately from the part itself
+    fn timed(&self, part: Part, input: &str) -> Result<Timed, Box<dyn Error>> {
+        let start = Instant::now();
+        let generated = self.generate(input)?;
+        let generate = start.elapsed();
+        let start = Instant::now();
+        let answer = generated(part)?;
+        Ok(Timed {
+            answer,
+            generate,
+            solve: start.elapsed(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Timed {
+    pub answer: String,
+    pub generate: Duration,
+    pub solve: Duration,
+}
+
+// Declares a day's Solution from its generator and part functions, which can
+// stay private to the day's module
+macro_rules! solver {
+    ($year:expr, $day:expr, $generator:ident, $part1:ident, $part2:ident) => {
+        pub struct Solution;
+
+        impl $crate::solver::Solver for Solution {
+            fn year(&self) -> u32 {
+                $year
+            }
+
+            fn day(&self) -> u32 {
+                $day
+            }
+
+            fn generate<'a>(
+                &self,
+                input: &'a str,
+            ) -> Result<$crate::solver::Generated<'a>, Box<dyn std::error::Error>> {
+                use $crate::solver::IntoAnswer;
+                let input = $generator(input)?;
+                Ok(Box::new(move |part| match part {
+                    $crate::solver::Part::One => $part1(&input).into_answer(),
+                    $crate::solver::Part::Two => $part2(&input).into_answer(),
+                }))
+            }
+        }
+    };
+}
+
+// Every solver, in year and then day order
+pub fn solvers() -> Vec<&'static dyn Solver> {
+    let years: [fn() -> Vec<&'static dyn Solver>; 1] = [y2020::solvers];
+    years.iter().flat_map(|solvers| solvers()).collect()
+}
+
+// Every year with at least one solver, in order
+pub fn years() -> Vec<u32> {
+    let mut years: Vec<u32> = solvers().iter().map(|s| s.year()).collect();
+    years.dedup();
+    years
+}
+
+pub fn solver(year: u32, day: u32) -> Option<&'static dyn Solver> {
+    solvers()
+        .into_iter()
+        .find(|s| s.year() == year && s.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let days: Vec<u32> = solvers().iter().map(|s| s.day()).collect();
+        assert_eq!(days, (1..=11).collect::<Vec<_>>());
+        assert_eq!(years(), vec![2020]);
+        assert!(solver(2020, 12).is_none());
+        assert!(solver(2019, 1).is_none());
+
+        let day1 = solver(2020, 1).unwrap();
+        assert_eq!((day1.year(), day1.day()), (2020, 1));
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        assert_eq!(day1.solve(Part::One, input).unwrap(), "514579");
+        assert_eq!(day1.solve(Part::Two, input).unwrap(), "241861950");
+        assert!(day1
+            .solve(Part::One, "1\n2\n")
+            .unwrap_err()
+            .is::<NoAnswer>());
+        assert!(day1.solve(Part::One, "x\n").is_err());
+
+        let timed = day1.timed(Part::Two, input).unwrap();
+        assert_eq!(timed.answer, "241861950");
+        let generated = day1.generate(input).unwrap();
+        assert_eq!(generated(Part::One).unwrap(), "514579");
+        assert_eq!(generated(Part::One).unwrap(), "514579");
+    }
+
+    #[test]
+    fn test_part() {
+        assert_eq!("2".parse::<Part>(), Ok(Part::Two));
+        assert!("3".parse::<Part>().is_err());
+        assert_eq!(Part::One.to_string(), "1");
+    }
+}
@@ -0,0 +1,480 @@
+use crate::inputs::{self, HttpError, BASE_URL};
+use crate::parse::{number, LineError};
+use crate::solver::Part;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// What the site said about a submitted answer
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    // Submitted too soon after the last answer, holds how long is left
+    Wait(Duration),
+    // The part was already solved, so the answer wasn't checked
+    AlreadySolved,
+    Unknown,
+}
+
+impl Verdict {
+    // Reads the verdict out of the page the site sends back
+    pub fn parse(page: &str) -> Verdict {
+        if page.contains("That's the right answer") {
+            Verdict::Correct
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if page.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            }
+        } else if page.contains("You gave an answer too recently") {
+            Verdict::Wait(parse_wait(page).unwrap_or_default())
+        } else if page.contains("You don't seem to be solving the right level") {
+            Verdict::AlreadySolved
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+
+    // How the verdict is written in the ledger, read back by from_str
+    fn key(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+            Verdict::Wait(_) => "wait",
+            Verdict::AlreadySolved => "already-solved",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+// The time in "You have 1m 5s left to wait."
+fn parse_wait(page: &str) -> Option<Duration> {
+    let end = page.find(" left to wait")?;
+    let start = page[..end].rfind("You have ")? + "You have ".len();
+    let mut seconds = 0;
+    for word in page[start..end].split_whitespace() {
+        let (n, unit) = word.split_at(word.find(|c: char| !c.is_ascii_digit())?);
+        let n: u64 = n.parse().ok()?;
+        seconds += match unit {
+            "h" => n * 3600,
+            "m" => n * 60,
+            "s" => n,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::Wait(left) => write!(f, "too soon, wait {}s", left.as_secs()),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Unknown => write!(f, "not understood"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "correct" => Ok(Verdict::Correct),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "wrong" => Ok(Verdict::Wrong),
+            "wait" => Ok(Verdict::Wait(Duration::default())),
+            "already-solved" => Ok(Verdict::AlreadySolved),
+            "unknown" => Ok(Verdict::Unknown),
+            _ => Err(format!("Invalid verdict {:?}.", input)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attempt {
+    // Seconds since the Unix epoch
+    pub time: u64,
+    pub year: u32,
+    pub day: u32,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+// Every answer ever submitted, one tab separated attempt per line:
+//
+//     time  year  day  part  verdict  answer
+#[derive(Debug)]
+pub struct Ledger {
+    pub path: PathBuf,
+    pub attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    // A ledger that doesn't exist yet is empty
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, SubmitError> {
+        let path = path.into();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(SubmitError::Ledger(path, e.to_string())),
+        };
+        let attempts = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_attempt(line).map_err(|e| {
+                    let at = format!("line {}, column {}: {}", i + 1, e.column, e.message);
+                    SubmitError::Ledger(path.clone(), at)
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Ledger { path, attempts })
+    }
+
+    // The earlier attempt that already settles answer, if there is one: the
+    // same answer, a correct one, or a bound that answer is past
+    pub fn known(&self, year: u32, day: u32, part: Part, answer: &str) -> Option<&Attempt> {
+        let n = answer.parse::<i128>().ok();
+        self.attempts
+            .iter()
+            .filter(|a| (a.year, a.day, a.part) == (year, day, part))
+            .find(|a| {
+                let bound = a.answer.parse::<i128>().ok();
+                match (&a.verdict, n, bound) {
+                    (Verdict::Correct, _, _) => true,
+                    (v, _, _) if v.is_wrong() && a.answer == answer => true,
+                    (Verdict::TooHigh, Some(n), Some(high)) => n >= high,
+                    (Verdict::TooLow, Some(n), Some(low)) => n <= low,
+                    _ => false,
+                }
+            })
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), SubmitError> {
+        let err = |e: io::Error| SubmitError::Ledger(self.path.clone(), e.to_string());
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(err)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(err)?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            attempt.time,
+            attempt.year,
+            attempt.day,
+            attempt.part,
+            attempt.verdict.key(),
+            attempt.answer
+        )
+        .map_err(err)?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+fn parse_attempt(line: &str) -> Result<Attempt, LineError> {
+    let fields: Vec<&str> = line.splitn(6, '\t').collect();
+    if fields.len() != 6 {
+        return Err(LineError::new(
+            1,
+            "expected time, year, day, part, verdict and answer",
+        ));
+    }
+    // Where field i starts, past the earlier fields and their tabs
+    let column = |i: usize| fields[..i].iter().map(|f| f.len() + 1).sum::<usize>() + 1;
+    Ok(Attempt {
+        time: number(fields[0], column(0))?,
+        year: number(fields[1], column(1))?,
+        day: number(fields[2], column(2))?,
+        part: fields[3]
+            .parse()
+            .map_err(|e| LineError::new(column(3), e))?,
+        verdict: fields[4]
+            .parse()
+            .map_err(|e| LineError::new(column(4), e))?,
+        answer: fields[5].to_string(),
+    })
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Http(HttpError),
+    // The ledger already settles this answer
+    Known(Attempt),
+    // The answer has a tab or line break, which would corrupt the ledger
+    Answer(String),
+    Ledger(PathBuf, String),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Http(e) => write!(f, "{}", e),
+            SubmitError::Known(a) if a.verdict == Verdict::Correct => write!(
+                f,
+                "{} day {} part {} was already solved with {}.",
+                a.year, a.day, a.part, a.answer
+            ),
+            SubmitError::Known(a) => write!(
+                f,
+                "Not submitting, {} was already {} for {} day {} part {}.",
+                a.answer, a.verdict, a.year, a.day, a.part
+            ),
+            SubmitError::Answer(answer) => write!(
+                f,
+                "Not submitting {:?}, an answer can't contain tabs or line breaks.",
+                answer
+            ),
+            SubmitError::Ledger(path, message) => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl Error for SubmitError {}
+
+impl From<HttpError> for SubmitError {
+    fn from(e: HttpError) -> Self {
+        SubmitError::Http(e)
+    }
+}
+
+// Posts answers to the site, checking and updating the ledger as it goes
+pub struct Submitter {
+    pub base_url: String,
+    pub session: Option<String>,
+    pub ledger: Ledger,
+}
+
+impl Submitter {
+    pub fn new(ledger: Ledger) -> Self {
+        Submitter {
+            base_url: BASE_URL.to_string(),
+            session: inputs::session(),
+            ledger,
+        }
+    }
+
+    pub fn submit(
+        &mut self,
+        year: u32,
+        day: u32,
+        part: Part,
+        answer: &str,
+    ) -> Result<Verdict, SubmitError> {
+        if answer.contains(['\t', '\n', '\r']) {
+            return Err(SubmitError::Answer(answer.to_string()));
+        }
+        if let Some(attempt) = self.ledger.known(year, day, part, answer) {
+            return Err(SubmitError::Known(attempt.clone()));
+        }
+        let session = self.session.as_ref().ok_or(HttpError::NoSession)?;
+        let url = format!(
+            "{}/{}/day/{}/answer",
+            self.base_url.trim_end_matches('/'),
+            year,
+            day
+        );
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", inputs::USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+        let page = inputs::read_response(url, response)?;
+
+        let verdict = Verdict::parse(&page);
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.ledger.record(Attempt {
+            time,
+            year,
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict: verdict.clone(),
+        })?;
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::Stub;
+    use std::env;
+    use std::process;
+
+    const RIGHT: &str =
+        "<article><p>That's the right answer!  You are one gold star closer.</p></article>";
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high.  \
+                            Please wait one minute before trying again.</p></article>";
+    const WAIT: &str = "<article><p>You gave an answer too recently; you have to wait after \
+                        submitting an answer before trying again.  You have 1m 5s left to wait. \
+                        </p></article>";
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(Verdict::parse(RIGHT), Verdict::Correct);
+        assert_eq!(Verdict::parse(TOO_HIGH), Verdict::TooHigh);
+        assert_eq!(
+            Verdict::parse(&TOO_HIGH.replace("too high", "too low")),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            Verdict::parse("That's not the right answer.  If you're stuck..."),
+            Verdict::Wrong
+        );
+        assert_eq!(Verdict::parse(WAIT), Verdict::Wait(Duration::from_secs(65)));
+        assert_eq!(
+            Verdict::parse(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ),
+            Verdict::AlreadySolved
+        );
+        assert_eq!(Verdict::parse("<html></html>"), Verdict::Unknown);
+    }
+
+    #[test]
+    fn test_ledger() {
+        let path = env::temp_dir().join(format!("aoc2020-ledger-{}.tsv", process::id()));
+        let _ = fs::remove_file(&path);
+        let mut ledger = Ledger::load(&path).unwrap();
+        let attempt = |answer: &str, verdict| Attempt {
+            time: 1_607_000_000,
+            year: 2020,
+            day: 9,
+            part: Part::One,
+            answer: answer.to_string(),
+            verdict,
+        };
+        ledger.record(attempt("500", Verdict::TooHigh)).unwrap();
+        ledger.record(attempt("20", Verdict::TooLow)).unwrap();
+        ledger.record(attempt("x", Verdict::Wrong)).unwrap();
+        ledger
+            .record(attempt("300", Verdict::Wait(Duration::from_secs(5))))
+            .unwrap();
+
+        let ledger = Ledger::load(&path).unwrap();
+        assert_eq!(ledger.attempts.len(), 4);
+        assert_eq!(ledger.attempts[0], attempt("500", Verdict::TooHigh));
+        let known = |answer| {
+            ledger
+                .known(2020, 9, Part::One, answer)
+                .map(|a| a.answer.as_str())
+        };
+        assert_eq!(known("600"), Some("500"));
+        assert_eq!(known("10"), Some("20"));
+        assert_eq!(known("x"), Some("x"));
+        assert_eq!(known("300"), None);
+        assert_eq!(ledger.known(2020, 9, Part::Two, "600"), None);
+
+        fs::write(&path, "1\t2020\t9\t1\tmaybe\t3\n").unwrap();
+        assert!(Ledger::load(&path)
+            .unwrap_err()
+            .to_string()
+            .ends_with("line 1, column 12: Invalid verdict \"maybe\"."));
+        fs::write(&path, "\n1\t2020\tnine\t1\twrong\t3\n").unwrap();
+        assert!(Ledger::load(&path)
+            .unwrap_err()
+            .to_string()
+            .contains("line 2, column 8: \"nine\" is not a number"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let stub = Stub::start(|request| {
+            let page = match request.body.as_str() {
+                "level=1&answer=514579" => RIGHT,
+                "level=2&answer=999" => TOO_HIGH,
+                _ => WAIT,
+            };
+            (200, page.to_string())
+        });
+        let path = env::temp_dir().join(format!("aoc2020-submit-{}.tsv", process::id()));
+        let _ = fs::remove_file(&path);
+        let mut submitter = Submitter {
+            base_url: stub.url.clone(),
+            session: Some("abc123".to_string()),
+            ledger: Ledger::load(&path).unwrap(),
+        };
+
+        assert_eq!(
+            submitter.submit(2020, 1, Part::Two, "999").unwrap(),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            submitter
+                .submit(2020, 1, Part::Two, "1000")
+                .unwrap_err()
+                .to_string(),
+            "Not submitting, 999 was already too high for 2020 day 1 part 2."
+        );
+        assert_eq!(
+            submitter.submit(2020, 1, Part::Two, "5").unwrap(),
+            Verdict::Wait(Duration::from_secs(65))
+        );
+        assert_eq!(
+            submitter.submit(2020, 1, Part::One, "514579").unwrap(),
+            Verdict::Correct
+        );
+        assert!(matches!(
+            submitter.submit(2020, 1, Part::One, "514579"),
+            Err(SubmitError::Known(_))
+        ));
+        assert_eq!(
+            submitter
+                .submit(2020, 1, Part::Two, "1\t2")
+                .unwrap_err()
+                .to_string(),
+            "Not submitting \"1\\t2\", an answer can't contain tabs or line breaks."
+        );
+        assert!(matches!(
+            submitter.submit(2020, 1, Part::Two, "12\n"),
+            Err(SubmitError::Answer(_))
+        ));
+
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2020/day/1/answer");
+        assert_eq!(requests[0].header("Cookie"), Some("session=abc123"));
+        assert_eq!(Ledger::load(&path).unwrap().attempts.len(), 3);
+
+        submitter.session = None;
+        assert!(matches!(
+            submitter.submit(2020, 2, Part::One, "1"),
+            Err(SubmitError::Http(HttpError::NoSession))
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,31 @@
+use crate::solver::Solver;
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod gen;
+
+// Every day's solver, in day order
+pub fn solvers() -> Vec<&'static dyn Solver> {
+    vec![
+        &day1::Solution,
+        &day2::Solution,
+        &day3::Solution,
+        &day4::Solution,
+        &day5::Solution,
+        &day6::Solution,
+        &day7::Solution,
+        &day8::Solution,
+        &day9::Solution,
+        &day10::Solution,
+        &day11::Solution,
+    ]
+}
@@ -0,0 +1,91 @@
+use crate::parse::{number, parse_lines, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
+
+#[aoc_generator(day1)]
+pub fn input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse_lines(1, input, |line| number(line, 1))
+}
+
+#[aoc(day1, part1)]
+pub fn find_2020_pairs(input: &[usize]) -> Option<usize> {
+    let mut pairs = input.iter().combinations(2);
+    pairs.find_map(|pair| {
+        if let [a, b] = pair[..] {
+            // A sum too big for usize can't be 2020
+            match a.checked_add(*b)? {
+                2020 => Some(a * b),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    })
+}
+
+#[aoc(day1, part2)]
+pub fn find_2020_triples(input: &[usize]) -> Option<usize> {
+    let mut triples = input.iter().combinations(3);
+    triples.find_map(|pair| {
+        if let [a, b, c] = pair[..] {
+            match a.checked_add(*b)?.checked_add(*c)? {
+                2020 => Some(a * b * c),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    })
+}
+
+solver!(2020, 1, input_generator, find_2020_pairs, find_2020_triples);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Reference versions that just try every pair and triple in order
+    fn naive_pairs(input: &[usize]) -> Option<usize> {
+        for i in 0..input.len() {
+            for j in i + 1..input.len() {
+                if input[i] + input[j] == 2020 {
+                    return Some(input[i] * input[j]);
+                }
+            }
+        }
+        None
+    }
+
+    fn naive_triples(input: &[usize]) -> Option<usize> {
+        for i in 0..input.len() {
+            for j in i + 1..input.len() {
+                for k in j + 1..input.len() {
+                    if input[i] + input[j] + input[k] == 2020 {
+                        return Some(input[i] * input[j] * input[k]);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = [usize::MAX, 2, 1000, usize::MAX - 1, 1020];
+        assert_eq!(find_2020_pairs(&input), Some(1000 * 1020));
+        assert_eq!(find_2020_triples(&input), None);
+    }
+
+    proptest! {
+        #[test]
+        fn pairs_match_naive(input in prop::collection::vec(0..2021usize, 0..40)) {
+            prop_assert_eq!(find_2020_pairs(&input), naive_pairs(&input));
+        }
+
+        #[test]
+        fn triples_match_naive(input in prop::collection::vec(0..2021usize, 0..30)) {
+            prop_assert_eq!(find_2020_triples(&input), naive_triples(&input));
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use crate::parse::{number, parse_lines, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::error::Error;
+use std::fmt;
+
+pub const MAX_GAP: usize = 3;
+
+// Outlet (0) followed by the distinct adaptors in ascending order. Adaptors
+// with the same joltage are interchangeable, so each joltage counts once in
+// both parts.
+fn joltages(input: &[usize]) -> Vec<usize> {
+    let mut jolts = input.to_vec();
+    jolts.push(0);
+    jolts.sort_unstable();
+    jolts.dedup();
+    jolts
+}
+
+// Adaptors from and to are more than max_gap apart, and count more spaced
+// evenly between them would close the gap
+#[derive(Debug, PartialEq)]
+pub struct Gap {
+    pub from: usize,
+    pub to: usize,
+    pub max_gap: usize,
+    pub count: usize,
+}
+
+impl Gap {
+    // None if max_gap is 0, as then no number of adaptors closes the gap
+    fn new(from: usize, to: usize, max_gap: usize) -> Option<Self> {
+        let count = (to - from).checked_sub(1)?.checked_div(max_gap)?;
+        Some(Gap {
+            from,
+            to,
+            max_gap,
+            count,
+        })
+    }
+
+    // The fewest extra adaptors that close the gap, spaced max_gap apart
+    pub fn adaptors(&self) -> impl Iterator<Item = usize> {
+        let (from, max_gap) = (self.from, self.max_gap);
+        (1..=self.count).map(move |k| from + k * max_gap)
+    }
+}
+
+// The first link in a chain that can't be made, where from and to are too far
+// apart
+#[derive(Debug, PartialEq)]
+pub struct BrokenChain {
+    pub from: usize,
+    pub to: usize,
+    // Sorted chain up to and including from
+    pub prefix: Vec<usize>,
+    // Every gap in the chain, not just the first, or none at all if max_gap
+    // is 0 and there's no closing them
+    pub gaps: Vec<Gap>,
+}
+
+impl BrokenChain {
+    // Fewest extra adaptors that close every gap in the chain, or None if
+    // nothing can
+    pub fn missing(&self) -> Option<usize> {
+        if self.gaps.is_empty() {
+            return None;
+        }
+        Some(self.gaps.iter().map(|gap| gap.count).sum())
+    }
+}
+
+impl fmt::Display for BrokenChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No adaptor between {} and {}", self.from, self.to)?;
+        if let Some(missing) = self.missing() {
+            write!(f, "; chain needs {} more", missing)?;
+        }
+        for (i, gap) in self.gaps.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { ", " };
+            write!(
+                f,
+                "{}{} between {} and {}",
+                sep, gap.count, gap.from, gap.to
+            )?;
+        }
+        write!(f, ".")
+    }
+}
+impl Error for BrokenChain {}
+
+// Count of each difference between consecutive joltages, where element i
+// counts differences of i + 1
+fn diff_dist(input: &[usize], max_gap: usize) -> Result<Vec<usize>, BrokenChain> {
+    let mut diff = vec![0; max_gap];
+    let jolts = joltages(input);
+    let mut broken = None;
+    let mut gaps = Vec::new();
+    for (i, (&x, &y)) in jolts.iter().zip(jolts[1..].iter()).enumerate() {
+        match y - x {
+            d if d <= max_gap => diff[d - 1] += 1,
+            _ => {
+                broken = broken.or(Some(i));
+                gaps.extend(Gap::new(x, y, max_gap));
+            }
+        };
+    }
+    match broken {
+        None => Ok(diff),
+        Some(i) => Err(BrokenChain {
+            from: jolts[i],
+            to: jolts[i + 1],
+            prefix: jolts[..=i].to_vec(),
+            gaps,
+        }),
+    }
+}
+
+#[aoc_generator(day10)]
+fn input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse_lines(10, input, |line| number(line, 1))
+}
+
+// The device's built-in adaptor is always max_gap above the largest one, so
+// it adds one more difference of max_gap
+#[aoc(day10, part1)]
+fn adaptor_chain(input: &[usize]) -> Result<usize, BrokenChain> {
+    let diff = diff_dist(input, MAX_GAP)?;
+    Ok(diff[0] * (diff[MAX_GAP - 1] + 1))
+}
+
+// Number of ways to get from the outlet to the largest adaptor, or None if it
+// doesn't fit in a u128
+pub fn count_arrangements(input: &[usize], max_gap: usize) -> Option<u128> {
+    let jolts = joltages(input);
+    let mut ways: Vec<u128> = vec![0; jolts.len()];
+    ways[0] = 1;
+    let mut first = 0;
+    for i in 1..jolts.len() {
+        while jolts[i] - jolts[first] > max_gap {
+            first += 1;
+        }
+        let mut sum: u128 = 0;
+        for w in &ways[first..i] {
+            sum = sum.checked_add(*w)?;
+        }
+        ways[i] = sum;
+    }
+    ways.last().copied()
+}
+
+// Lazily walks every arrangement in lexicographic order, yielding the adaptors
+// used (without the outlet)
+pub struct Arrangements {
+    jolts: Vec<usize>,
+    max_gap: usize,
+    // Index into jolts of each adaptor on the current path, and the next
+    // candidate to try after it
+    stack: Vec<(usize, usize)>,
+}
+
+impl Arrangements {
+    pub fn new(input: &[usize], max_gap: usize) -> Self {
+        Arrangements {
+            jolts: joltages(input),
+            max_gap,
+            stack: vec![(0, 1)],
+        }
+    }
+}
+
+impl Iterator for Arrangements {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.jolts.len() - 1;
+        loop {
+            let (i, next) = self.stack.last_mut()?;
+            let i = *i;
+            if i == last {
+                let chain = self.stack[1..]
+                    .iter()
+                    .map(|&(j, _)| self.jolts[j])
+                    .collect();
+                self.stack.pop();
+                return Some(chain);
+            }
+            if *next <= last && self.jolts[*next] - self.jolts[i] <= self.max_gap {
+                let j = *next;
+                *next += 1;
+                self.stack.push((j, j + 1));
+            } else {
+                self.stack.pop();
+            }
+        }
+    }
+}
+
+#[aoc(day10, part2)]
+fn adaptor_options(input: &[usize]) -> Option<u128> {
+    count_arrangements(input, MAX_GAP)
+}
+
+solver!(2020, 10, input_generator, adaptor_chain, adaptor_options);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const LARGER: [usize; 31] = [
+        28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8,
+        17, 7, 9, 4, 2, 34, 10, 3,
+    ];
+
+    #[test]
+    fn test_one() {
+        let actual = diff_dist(&[0usize, 16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4], MAX_GAP);
+        assert_eq!(actual, Ok(vec![7, 0, 4]));
+    }
+
+    #[test]
+    fn test_two() {
+        let actual = diff_dist(
+            &[
+                0usize, 28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1,
+                32, 25, 35, 8, 17, 7, 9, 4, 2, 34, 10, 3,
+            ],
+            MAX_GAP,
+        );
+        assert_eq!(actual, Ok(vec![22, 0, 9]));
+    }
+
+    #[test]
+    fn test_gap() {
+        assert_eq!(diff_dist(&[0, 1, 5, 6], 4), Ok(vec![2, 0, 0, 1]));
+        assert_eq!(count_arrangements(&[1, 2, 3, 4], 1), Some(1));
+        assert_eq!(count_arrangements(&[1, 2, 3, 4], 4), Some(8));
+    }
+
+    #[test]
+    fn broken_chain() {
+        let broken = diff_dist(&[0, 1, 2, 6, 7, 20], MAX_GAP).unwrap_err();
+        assert_eq!(
+            broken,
+            BrokenChain {
+                from: 2,
+                to: 6,
+                prefix: vec![0, 1, 2],
+                gaps: vec![
+                    Gap {
+                        from: 2,
+                        to: 6,
+                        max_gap: MAX_GAP,
+                        count: 1
+                    },
+                    Gap {
+                        from: 7,
+                        to: 20,
+                        max_gap: MAX_GAP,
+                        count: 4
+                    },
+                ],
+            }
+        );
+        let adaptors: Vec<_> = broken.gaps.iter().flat_map(Gap::adaptors).collect();
+        assert_eq!(adaptors, vec![5, 10, 13, 16, 19]);
+        assert_eq!(broken.missing(), Some(5));
+        assert_eq!(diff_dist(&[0, 4], 2).unwrap_err().missing(), Some(1));
+        // Nothing closes a gap when no difference is allowed at all
+        let stuck = diff_dist(&[0, 1], 0).unwrap_err();
+        assert_eq!(stuck.missing(), None);
+        assert_eq!(stuck.to_string(), "No adaptor between 0 and 1.");
+        assert_eq!(
+            adaptor_chain(&[4, 5]).unwrap_err().to_string(),
+            "No adaptor between 0 and 4; chain needs 1 more: 1 between 0 and 4."
+        );
+        assert_eq!(
+            adaptor_chain(&[4, 5, 9]).unwrap_err().to_string(),
+            "No adaptor between 0 and 4; chain needs 2 more: 1 between 0 and 4, 1 between 5 and 9."
+        );
+        assert_eq!(adaptor_chain(&[3, 4]), Ok(2));
+    }
+
+    #[test]
+    fn huge_gap() {
+        let broken = adaptor_chain(&[1, usize::MAX]).unwrap_err();
+        assert_eq!(broken.gaps.len(), 1);
+        assert_eq!(
+            broken.missing(),
+            Some((usize::MAX - 1).div_ceil(MAX_GAP) - 1)
+        );
+        assert_eq!(broken.gaps[0].adaptors().next(), Some(4));
+        assert_eq!(count_arrangements(&[1, usize::MAX], MAX_GAP), Some(0));
+    }
+
+    #[test]
+    fn duplicates() {
+        // Adaptors with the same joltage count once in both parts
+        let input = [1, 1, 2, 4, 4, 0];
+        assert_eq!(adaptor_chain(&input), adaptor_chain(&[1, 2, 4]));
+        assert_eq!(adaptor_chain(&input), Ok(2));
+        assert_eq!(adaptor_options(&input), adaptor_options(&[1, 2, 4]));
+        assert_eq!(
+            Arrangements::new(&input, MAX_GAP).count(),
+            Arrangements::new(&[1, 2, 4], MAX_GAP).count()
+        );
+    }
+
+    #[test]
+    fn generator() {
+        assert_eq!(input_generator("1\n2\n"), Ok(vec![1, 2]));
+        assert_eq!(input_generator("1\nx\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(adaptor_options(&[1usize, 2, 3, 4]), Some(7));
+        assert_eq!(adaptor_options(&[1usize, 2, 4, 6, 7]), Some(6));
+        assert_eq!(
+            adaptor_options(&[16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4]),
+            Some(8)
+        );
+        assert_eq!(adaptor_options(&LARGER), Some(19208));
+    }
+
+    #[test]
+    fn options_overflow() {
+        // Tribonacci numbers pass u128::MAX before 200 consecutive adaptors
+        let chain: Vec<usize> = (1..100).collect();
+        assert!(count_arrangements(&chain, MAX_GAP).is_some());
+        let chain: Vec<usize> = (1..200).collect();
+        assert_eq!(count_arrangements(&chain, MAX_GAP), None);
+    }
+
+    #[test]
+    fn arrangements() {
+        let all: Vec<_> = Arrangements::new(&[16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4], 3).collect();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0], vec![1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19]);
+        assert_eq!(all[7], vec![1, 4, 7, 10, 12, 15, 16, 19]);
+        assert_eq!(Arrangements::new(&LARGER, 3).count(), 19208);
+        assert_eq!(Arrangements::new(&[], 3).collect::<Vec<_>>(), vec![vec![]]);
+        assert_eq!(Arrangements::new(&[1, 5], 3).next(), None);
+    }
+
+    // Reference part 1: sort, then count the differences of one and three
+    fn naive_chain(input: &[usize]) -> Option<usize> {
+        let mut jolts = input.to_vec();
+        jolts.push(0);
+        jolts.sort_unstable();
+        let (mut ones, mut threes) = (0, 1);
+        for pair in jolts.windows(2) {
+            match pair[1] - pair[0] {
+                1 => ones += 1,
+                2 => (),
+                3 => threes += 1,
+                _ => return None,
+            }
+        }
+        Some(ones * threes)
+    }
+
+    // Reference part 2: try every subset of the adaptors below the largest
+    fn naive_options(input: &[usize]) -> u128 {
+        let mut jolts = input.to_vec();
+        jolts.sort_unstable();
+        let last = jolts.pop().unwrap();
+        (0..1u32 << jolts.len())
+            .filter(|mask| {
+                let mut chain = vec![0];
+                chain.extend(
+                    (0..jolts.len())
+                        .filter(|i| mask >> i & 1 == 1)
+                        .map(|i| jolts[i]),
+                );
+                chain.push(last);
+                chain.windows(2).all(|pair| pair[1] - pair[0] <= MAX_GAP)
+            })
+            .count() as u128
+    }
+
+    proptest! {
+        // Mostly gaps the chain can bridge, with the odd one of four that it can't
+        #[test]
+        fn parts_match_naive(gaps in prop::collection::vec(1..=MAX_GAP + 1, 1..13)) {
+            let input: Vec<usize> = gaps
+                .iter()
+                .scan(0, |jolts, gap| {
+                    *jolts += gap;
+                    Some(*jolts)
+                })
+                .collect();
+            prop_assert_eq!(adaptor_chain(&input).ok(), naive_chain(&input));
+            prop_assert_eq!(adaptor_options(&input), Some(naive_options(&input)));
+            prop_assert_eq!(
+                Arrangements::new(&input, MAX_GAP).count() as u128,
+                naive_options(&input)
+            );
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::parse::{number, parse_lines, LineError, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+use regex::Regex;
+
+#[derive(Debug)]
+struct Password {
+    min: usize,
+    max: usize,
+    character: char,
+    password: String,
+}
+
+#[aoc_generator(day2)]
+fn input_generator(input: &str) -> Result<Vec<Password>, ParseError> {
+    let pattern: Regex = Regex::new(r"^(\d+)-(\d+) ([a-z]): ([a-z]+)$").unwrap();
+    parse_lines(2, input, |line| {
+        let entry = pattern
+            .captures(line)
+            .ok_or_else(|| LineError::new(1, "expected a policy like 1-3 a: abcde"))?;
+        let field = |i| entry.get(i).unwrap();
+        Ok(Password {
+            min: number(field(1).as_str(), field(1).start() + 1)?,
+            max: number(field(2).as_str(), field(2).start() + 1)?,
+            character: field(3).as_str().chars().next().unwrap(),
+            password: String::from(field(4).as_str()),
+        })
+    })
+}
+
+#[aoc(day2, part1)]
+fn valid_passwords_count(input: &[Password]) -> usize {
+    input
+        .iter()
+        .filter(|entry| {
+            let count = entry.password.matches(entry.character).count();
+            entry.min <= count && entry.max >= count
+        })
+        .count()
+}
+
+#[aoc(day2, part2)]
+fn valid_passwords_position(input: &[Password]) -> usize {
+    input
+        .iter()
+        .filter(|entry| {
+            entry
+                .password
+                .chars()
+                .enumerate()
+                .filter(|(i, ch)| {
+                    *ch == entry.character && (*i + 1 == entry.min || *i + 1 == entry.max)
+                })
+                .count()
+                == 1
+        })
+        .count()
+}
+
+solver!(
+    2020,
+    2,
+    input_generator,
+    valid_passwords_count,
+    valid_passwords_position
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
+    #[test]
+    fn test_parts() {
+        let input = input_generator(INPUT).unwrap();
+        assert_eq!(valid_passwords_count(&input), 2);
+        assert_eq!(valid_passwords_position(&input), 1);
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        let err = input_generator("1-3 a: abcde\n1-3 b cdefg\n").unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (2, 2, 1));
+        assert_eq!(err.message, "expected a policy like 1-3 a: abcde");
+        // The column points at the number that doesn't fit
+        let err = input_generator("1-3 a: abcde\n1-99999999999999999999999 b: c\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use aoc_runner_derive::{aoc, aoc_generator};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Cell {
+    Tree,
+    Snow,
+}
+
+impl Cell {
+    fn parse(ch: char) -> Result<Self, String> {
+        match ch {
+            '#' => Ok(Cell::Tree),
+            '.' => Ok(Cell::Snow),
+            _ => Err(format!("unexpected character {}", ch)),
+        }
+    }
+}
+
+// The pattern repeats to the right forever
+type Model = Grid<Cell>;
+
+#[aoc_generator(day3)]
+fn input_generator(input: &str) -> Result<Model, ParseError> {
+    Grid::parse(input, Cell::parse).map_err(|e| ParseError::from_grid(3, input, e))
+}
+
+fn count_trees(input: &Model, (down, right): (usize, usize)) -> usize {
+    let rows = (0..input.height()).step_by(down);
+    let cols = (0..).step_by(right);
+    rows.zip(cols)
+        .filter(|&(row, col)| input.get_wrapping(row as isize, col as isize) == Some(&Cell::Tree))
+        .count()
+}
+
+#[aoc(day3, part1)]
+fn count_trees_from_corner(input: &Model) -> usize {
+    count_trees(input, (1, 3))
+}
+
+#[aoc(day3, part2)]
+fn count_trees_with_slopes(input: &Model) -> usize {
+    let slopes = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
+    slopes
+        .iter()
+        .map(|&slope| count_trees(input, slope))
+        .product()
+}
+
+solver!(
+    2020,
+    3,
+    input_generator,
+    count_trees_from_corner,
+    count_trees_with_slopes
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "..##.......\n#...#...#..\n.#....#..#.\n..#.#...#.#\n.#...##..#.\n\
+                         ..#.##.....\n.#.#.#....#\n.#........#\n#.##...#...\n#...##....#\n\
+                         .#..#...#.#\n";
+
+    #[test]
+    fn test_parts() {
+        let input = input_generator(INPUT).unwrap();
+        assert_eq!(count_trees_from_corner(&input), 7);
+        assert_eq!(count_trees_with_slopes(&input), 336);
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        let err = input_generator("..#\n.x.\n").unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (3, 2, 2));
+        assert_eq!(err.message, "unexpected character x");
+        let err = input_generator("..#\n.#.\n.#\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 3));
+    }
+}
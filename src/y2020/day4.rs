@@ -1,8 +1,10 @@
+use crate::parse::{words, LineError, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::result::Result;
 
+#[derive(Debug)]
 struct Passport {
     byr: Option<usize>,
     iyr: Option<usize>,
@@ -30,9 +32,9 @@ impl Passport {
 
     fn set(&mut self, field: &str, value: &str) -> Result<(), &str> {
         match field {
-            "byr" => self.byr = Some(value.parse::<usize>().unwrap()),
-            "iyr" => self.iyr = Some(value.parse::<usize>().unwrap()),
-            "eyr" => self.eyr = Some(value.parse::<usize>().unwrap()),
+            "byr" => self.byr = Some(value.parse::<usize>().map_err(|_| "Invalid year.")?),
+            "iyr" => self.iyr = Some(value.parse::<usize>().map_err(|_| "Invalid year.")?),
+            "eyr" => self.eyr = Some(value.parse::<usize>().map_err(|_| "Invalid year.")?),
             "hgt" => self.hgt = Some(String::from(value)),
             "hcl" => self.hcl = Some(String::from(value)),
             "ecl" => self.ecl = Some(String::from(value)),
@@ -63,17 +65,15 @@ impl Passport {
 
     fn valid_height(height: &Option<String>) -> Option<usize> {
         let hgt = height.as_ref()?;
-        if hgt.len() < 3 {
-            return None;
-        }
-
-        let suffix = &hgt[hgt.len() - 2..];
-        let val = hgt[..hgt.len() - 2].parse::<usize>().ok()?;
-        match suffix {
-            "in" if val >= 59 && val <= 76 => Some(val),
-            "cm" if val >= 150 && val <= 193 => Some(val),
-            _ => None,
+        let (val, range) = match hgt.strip_suffix("in") {
+            Some(val) => (val, 59..=76),
+            None => (hgt.strip_suffix("cm")?, 150..=193),
+        };
+        let val = val.parse::<usize>().ok()?;
+        if range.contains(&val) {
+            return Some(val);
         }
+        None
     }
 
     fn valid_hcl(haircolour: &Option<String>) -> Option<&String> {
@@ -119,38 +119,71 @@ impl Passport {
 }
 
 #[aoc_generator(day4)]
-fn input_generator(input: &str) -> Vec<Passport> {
-    input
-        .split("\n\n")
-        .map(|entry| parse_passport(entry))
-        .collect()
+fn input_generator(input: &str) -> Result<Vec<Passport>, ParseError> {
+    // Passports are separated by blank lines and can span several lines
+    let mut passports = Vec::new();
+    let mut current = None;
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            passports.extend(current.take());
+            continue;
+        }
+        let passport = current.get_or_insert_with(Passport::new);
+        parse_fields(line, passport).map_err(|e| ParseError::from_line(4, input, i + 1, e))?;
+    }
+    passports.extend(current);
+    Ok(passports)
 }
 
-fn parse_passport(entry: &str) -> Passport {
-    let mut passport = Passport::new();
-    let fields = entry.split_whitespace();
-    for field in fields {
-        let mut parts = field.split(':');
-        let name = parts.next().unwrap();
-        let value = parts.next().unwrap();
-        passport.set(name, value).unwrap();
+const FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+fn parse_fields(line: &str, passport: &mut Passport) -> Result<(), LineError> {
+    for (column, field) in words(line) {
+        let (name, value) = match field.find(':') {
+            Some(i) => (&field[..i], &field[i + 1..]),
+            None => return Err(LineError::new(column, "expected name:value")),
+        };
+        if !FIELDS.contains(&name) {
+            return Err(LineError::new(column, format!("unknown field {:?}", name)));
+        }
+        passport
+            .set(name, value)
+            .map_err(|e| LineError::new(column + name.len() + 1, e))?;
     }
-    passport
+    Ok(())
 }
 
 #[aoc(day4, part1)]
-fn check_filled(passports: &Vec<Passport>) -> usize {
+fn check_filled(passports: &[Passport]) -> usize {
     passports.iter().filter(|&p| p.is_filled()).count()
 }
 
 #[aoc(day4, part2)]
-fn check_valid(passports: &Vec<Passport>) -> usize {
+fn check_valid(passports: &[Passport]) -> usize {
     passports.iter().filter(|&p| p.is_valid()).count()
 }
 
+solver!(2020, 4, input_generator, check_filled, check_valid);
+
 #[cfg(test)]
 mod tests {
-    use super::Passport;
+    use super::{input_generator, Passport};
+
+    #[test]
+    fn generator() {
+        let input = "ecl:gry pid:860033327\nbyr:1937\n\n\nhcl:#cfa07d byr:1929\n";
+        let passports = input_generator(input).unwrap();
+        assert_eq!(passports.len(), 2);
+        assert_eq!(passports[0].byr, Some(1937));
+        assert_eq!(passports[1].hcl, Some("#cfa07d".to_string()));
+
+        let err = input_generator("ecl:gry\n\npid:1 byr:19x7\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 11));
+        let err = input_generator("ecl:gry foo:1").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 9));
+        assert!(input_generator("ecl").is_err());
+    }
+
     #[test]
     fn yr() {
         assert!(Passport::valid_year(None, 1920, 2002).is_none());
@@ -170,6 +203,8 @@ mod tests {
         assert!(Passport::valid_height(&Some("59in".to_string())).is_some());
         assert!(Passport::valid_height(&Some("76in".to_string())).is_some());
         assert!(Passport::valid_height(&Some("77in".to_string())).is_none());
+        assert!(Passport::valid_height(&Some("cm".to_string())).is_none());
+        assert!(Passport::valid_height(&Some("1€".to_string())).is_none());
     }
     #[test]
     fn hcl() {
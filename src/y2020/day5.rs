@@ -0,0 +1,153 @@
+use crate::parse::{parse_lines, LineError, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+
+#[derive(Debug, PartialEq)]
+struct BoardingPass {
+    row: usize,
+    col: usize,
+    id: usize,
+}
+
+impl BoardingPass {
+    // Seven F/B characters for the row then three L/R for the column
+    fn parse(input: &str) -> Result<Self, LineError> {
+        let mut row = 0;
+        let mut col = 0;
+        for (i, ch) in input.chars().enumerate() {
+            match (i, ch) {
+                (0..=6, 'F') => row <<= 1,
+                (0..=6, 'B') => row = row << 1 | 1,
+                (7..=9, 'L') => col <<= 1,
+                (7..=9, 'R') => col = col << 1 | 1,
+                (0..=6, _) => return Err(LineError::new(i + 1, "expected F or B")),
+                (7..=9, _) => return Err(LineError::new(i + 1, "expected L or R")),
+                _ => return Err(LineError::new(i + 1, "expected 10 characters")),
+            }
+        }
+        match input.chars().count() {
+            10 => Ok(BoardingPass::from((row, col))),
+            n => Err(LineError::new(n + 1, "expected 10 characters")),
+        }
+    }
+}
+
+impl From<(usize, usize)> for BoardingPass {
+    fn from((row, col): (usize, usize)) -> Self {
+        BoardingPass {
+            row,
+            col,
+            id: row * 8 + col,
+        }
+    }
+}
+
+#[aoc_generator(day5)]
+fn input_generator(input: &str) -> Result<Vec<BoardingPass>, ParseError> {
+    parse_lines(5, input, BoardingPass::parse)
+}
+
+#[aoc(day5, part1)]
+fn highest_seat(passes: &[BoardingPass]) -> Option<usize> {
+    passes.iter().map(|pass| pass.id).max()
+}
+
+#[aoc(day5, part2)]
+fn missing_seat(passes: &[BoardingPass]) -> Option<usize> {
+    let mut ids: Vec<usize> = passes.iter().map(|pass| pass.id).collect();
+    ids.sort_unstable();
+    // Duplicate ids don't leave a gap
+    ids.windows(2)
+        .find(|pair| pair[1] > pair[0] + 1)
+        .map(|pair| pair[0] + 1)
+}
+
+solver!(2020, 5, input_generator, highest_seat, missing_seat);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn valid_boardpasses() {
+        assert_eq!(
+            BoardingPass::parse("FBFBBFFRLR"),
+            Ok(BoardingPass::from((44, 5)))
+        );
+        assert_eq!(
+            BoardingPass::parse("BFFFBBFRRR"),
+            Ok(BoardingPass::from((70, 7)))
+        );
+        assert_eq!(
+            BoardingPass::parse("FFFBBBFRRR"),
+            Ok(BoardingPass::from((14, 7)))
+        );
+        assert_eq!(
+            BoardingPass::parse("BBFFBBFRLL"),
+            Ok(BoardingPass::from((102, 4)))
+        );
+    }
+
+    #[test]
+    fn invalid_boardpasses() {
+        assert_eq!(BoardingPass::parse("FBFBBFFRL").unwrap_err().column, 10);
+        assert_eq!(BoardingPass::parse("FBFBBFFRLRR").unwrap_err().column, 11);
+        assert_eq!(BoardingPass::parse("FBFLBFFRLR").unwrap_err().column, 4);
+        let err = input_generator("FBFBBFFRLR\nFBFBBFFRXR\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 9));
+        assert_eq!(err.message, "expected L or R");
+    }
+
+    #[test]
+    fn no_gap() {
+        assert_eq!(missing_seat(&[]), None);
+        let passes = input_generator("FFFFFFFLLL\nFFFFFFFLLL\nFFFFFFFLLR\n").unwrap();
+        assert_eq!(missing_seat(&passes), None);
+    }
+
+    // Reference decoding: the pass is just a binary number with B and R as 1
+    fn naive_id(pass: &str) -> usize {
+        let bits: String = pass
+            .chars()
+            .map(|c| if c == 'B' || c == 'R' { '1' } else { '0' })
+            .collect();
+        usize::from_str_radix(&bits, 2).unwrap()
+    }
+
+    fn encode(id: usize) -> String {
+        (0..10)
+            .rev()
+            .map(|bit| match (bit >= 3, id >> bit & 1) {
+                (true, 0) => 'F',
+                (true, _) => 'B',
+                (false, 0) => 'L',
+                (false, _) => 'R',
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn parse_matches_naive(id in 0..1024usize) {
+            let pass = encode(id);
+            prop_assert_eq!(naive_id(&pass), id);
+            prop_assert_eq!(BoardingPass::parse(&pass), Ok(BoardingPass::from((id / 8, id % 8))));
+        }
+
+        #[test]
+        fn finds_missing_seat(
+            (ids, hole) in (0..900usize, 3..120usize)
+                .prop_flat_map(|(first, len)| (Just(first), Just(len), 1..len - 1))
+                .prop_flat_map(|(first, len, hole)| {
+                    let ids: Vec<usize> =
+                        (first..first + len).filter(|&id| id != first + hole).collect();
+                    (Just(ids).prop_shuffle(), Just(first + hole))
+                })
+        ) {
+            let input: String = ids.iter().map(|&id| encode(id) + "\n").collect();
+            let passes = input_generator(&input).unwrap();
+            prop_assert_eq!(missing_seat(&passes), Some(hole));
+            prop_assert_eq!(highest_seat(&passes), ids.iter().copied().max());
+        }
+    }
+}
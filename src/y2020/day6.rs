@@ -1,5 +1,5 @@
+use crate::parse::ParseError;
 use aoc_runner_derive::{aoc, aoc_generator};
-use regex::Regex;
 use std::collections::HashSet;
 
 #[derive(Debug, PartialEq)]
@@ -28,32 +28,40 @@ impl Group {
             self.intersection = self
                 .intersection
                 .intersection(&question.chars().collect())
-                .map(|&c| c)
+                .copied()
                 .collect()
         }
         self.count += 1;
     }
 }
 
-impl From<&str> for Group {
-    fn from(input: &str) -> Self {
-        let mut group = Group::new();
-        let members = input.lines();
-        for member in members {
-            group.add(member);
+// Groups are separated by blank lines, with one member's answers per line
+#[aoc_generator(day6)]
+fn input_generator(input: &str) -> Result<Vec<Group>, ParseError> {
+    let mut groups = Vec::new();
+    let mut current = None;
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            groups.extend(current.take());
+            continue;
+        }
+        if let Some(j) = line.find(|c: char| !c.is_ascii_lowercase()) {
+            return Err(ParseError::new(
+                6,
+                input,
+                i + 1,
+                line[..j].chars().count() + 1,
+                "expected questions a to z",
+            ));
         }
-        group
+        current.get_or_insert_with(Group::new).add(line);
     }
-}
-
-#[aoc_generator(day6)]
-fn input_generator(input: &str) -> Vec<Group> {
-    let pattern = Regex::new(r"(\r\n|\n|\r){2,}").unwrap();
-    pattern.split(input).map(|g| Group::from(g)).collect()
+    groups.extend(current);
+    Ok(groups)
 }
 
 #[aoc(day6, part1)]
-fn count_union(input: &Vec<Group>) -> usize {
+fn count_union(input: &[Group]) -> usize {
     let mut count = 0;
     for grp in input {
         count += grp.union.len();
@@ -62,7 +70,7 @@ fn count_union(input: &Vec<Group>) -> usize {
 }
 
 #[aoc(day6, part2)]
-fn count_intersection(input: &Vec<Group>) -> usize {
+fn count_intersection(input: &[Group]) -> usize {
     let mut count = 0;
     for grp in input {
         count += grp.intersection.len();
@@ -70,6 +78,8 @@ fn count_intersection(input: &Vec<Group>) -> usize {
     count
 }
 
+solver!(2020, 6, input_generator, count_union, count_intersection);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,6 +96,16 @@ mod tests {
         actual.add("cd");
         assert_eq!(expected, actual);
     }
+    #[test]
+    fn generator() {
+        let groups = input_generator("abc\n\na\nb\nc\n\nab\nac\n").unwrap();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[1].count, 3);
+        assert_eq!(groups[2].intersection, "a".chars().collect());
+        let err = input_generator("abc\n\na\naB\n").unwrap_err();
+        assert_eq!((err.line, err.column), (4, 2));
+    }
+
     #[test]
     fn group_intersection() {
         let expected = Group {
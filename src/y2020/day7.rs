@@ -0,0 +1,219 @@
+use crate::parse::{number, parse_lines, LineError, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+struct Colour(String);
+
+impl From<&str> for Colour {
+    fn from(s: &str) -> Colour {
+        Colour(s.to_string())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Content {
+    colour: Colour,
+    count: usize,
+}
+
+#[derive(Debug)]
+struct RuleSet {
+    rules: HashMap<Colour, Vec<Content>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum CountError {
+    // A bag that ends up inside itself, so there's no end to the count
+    Cycle(Colour),
+    Overflow,
+}
+
+impl fmt::Display for CountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountError::Cycle(colour) => write!(f, "A {} bag ends up inside itself.", colour.0),
+            CountError::Overflow => write!(f, "Too many bags to count."),
+        }
+    }
+}
+impl Error for CountError {}
+
+impl RuleSet {
+    // Colours whose bags can hold a to bag at any depth, found by walking the
+    // rules backwards from to so cycles end the walk rather than recursing
+    fn holders(&self, to: &Colour) -> HashSet<&Colour> {
+        let mut held_by: HashMap<&Colour, Vec<&Colour>> = HashMap::new();
+        for (from, contents) in &self.rules {
+            for c in contents {
+                held_by.entry(&c.colour).or_default().push(from);
+            }
+        }
+        let mut found = HashSet::new();
+        let mut stack = vec![to];
+        while let Some(colour) = stack.pop() {
+            for &from in held_by.get(colour).into_iter().flatten() {
+                if found.insert(from) {
+                    stack.push(from);
+                }
+            }
+        }
+        found
+    }
+
+    fn count_contents(&self, from: &Colour) -> Result<usize, CountError> {
+        self.count_memo(from, &mut HashMap::new())
+    }
+
+    // Each colour is counted once, with None in counts while it's being
+    // counted so reaching it again means a cycle
+    fn count_memo<'a>(
+        &'a self,
+        from: &'a Colour,
+        counts: &mut HashMap<&'a Colour, Option<usize>>,
+    ) -> Result<usize, CountError> {
+        match counts.get(from) {
+            Some(Some(count)) => return Ok(*count),
+            Some(None) => return Err(CountError::Cycle(from.clone())),
+            None => (),
+        }
+        counts.insert(from, None);
+        let mut total: usize = 0;
+        for c in self.rules.get(from).into_iter().flatten() {
+            let inside = self.count_memo(&c.colour, counts)?;
+            total = inside
+                .checked_add(1)
+                .and_then(|bags| bags.checked_mul(c.count))
+                .and_then(|bags| bags.checked_add(total))
+                .ok_or(CountError::Overflow)?;
+        }
+        counts.insert(from, Some(total));
+        Ok(total)
+    }
+}
+
+fn parse_rule(input: &str) -> Result<(Colour, Vec<Content>), LineError> {
+    let pattern = Regex::new(r"((\d+) )?([a-z ]+?) bag").unwrap();
+    let mut caps = pattern.captures_iter(input);
+    let colour = caps
+        .next()
+        .ok_or_else(|| LineError::new(1, "expected a rule like \"light red bags contain ...\""))?
+        .get(3)
+        .unwrap()
+        .as_str();
+    let mut contents = Vec::new();
+    for cap in caps {
+        if let Some(num) = cap.get(2) {
+            let count = number(num.as_str(), num.start() + 1)?;
+            let colour = cap.get(3).unwrap().as_str();
+            contents.push(Content {
+                colour: Colour::from(colour),
+                count,
+            });
+        }
+    }
+    Ok((Colour::from(colour), contents))
+}
+
+#[aoc_generator(day7)]
+fn input_generator(input: &str) -> Result<RuleSet, ParseError> {
+    let rules = parse_lines(7, input, parse_rule)?;
+    Ok(RuleSet {
+        rules: rules.into_iter().collect(),
+    })
+}
+
+#[aoc(day7, part1)]
+fn have_gold_bags(ruleset: &RuleSet) -> usize {
+    ruleset.holders(&Colour::from("shiny gold")).len()
+}
+
+#[aoc(day7, part2)]
+fn in_gold_bags(ruleset: &RuleSet) -> Result<usize, CountError> {
+    ruleset.count_contents(&Colour::from("shiny gold"))
+}
+
+solver!(2020, 7, input_generator, have_gold_bags, in_gold_bags);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule_with_contents() {
+        let actual = parse_rule("light red bags contain 1 bright white bag, 2 muted yellow bags.");
+        let expected = (
+            Colour::from("light red"),
+            vec![
+                Content {
+                    colour: Colour::from("bright white"),
+                    count: 1,
+                },
+                Content {
+                    colour: Colour::from("muted yellow"),
+                    count: 2,
+                },
+            ],
+        );
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_rule_without_contents() {
+        let actual = parse_rule("faded blue bags contain no other bags.");
+        let expected = (Colour::from("faded blue"), Vec::new());
+        assert_eq!(actual, Ok(expected));
+    }
+
+    #[test]
+    fn test_parse_rule_invalid() {
+        assert_eq!(parse_rule("").unwrap_err().column, 1);
+        let err = input_generator(
+            "faded blue bags contain no other bags.\n\
+             light red bags contain 99999999999999999999 bright white bags.",
+        )
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 24));
+    }
+
+    #[test]
+    fn test_parts() {
+        let rules = input_generator(
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.\n\
+             dark orange bags contain 3 bright white bags, 4 muted yellow bags.\n\
+             bright white bags contain 1 shiny gold bag.\n\
+             muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\n\
+             shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.\n\
+             dark olive bags contain 3 faded blue bags, 4 dotted black bags.\n\
+             vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.\n\
+             faded blue bags contain no other bags.\n\
+             dotted black bags contain no other bags.",
+        )
+        .unwrap();
+        assert_eq!(have_gold_bags(&rules), 4);
+        assert_eq!(in_gold_bags(&rules), Ok(32));
+    }
+
+    #[test]
+    fn test_bad_rules() {
+        let cycle = input_generator(
+            "shiny gold bags contain 1 dark red bag.\n\
+             dark red bags contain 2 shiny gold bags.",
+        )
+        .unwrap();
+        assert_eq!(have_gold_bags(&cycle), 2);
+        assert_eq!(
+            in_gold_bags(&cycle).unwrap_err().to_string(),
+            "A shiny gold bag ends up inside itself."
+        );
+        let huge = input_generator(
+            "shiny gold bags contain 9999999999 dark red bags.\n\
+             dark red bags contain 9999999999 faded blue bags.",
+        )
+        .unwrap();
+        assert_eq!(in_gold_bags(&huge), Err(CountError::Overflow));
+    }
+}
@@ -0,0 +1,938 @@
+use crate::parse::{parse_lines, LineError, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub mod cfg;
+
+use cfg::{Cfg, Cycle};
+
+#[derive(Clone, Debug)]
+pub enum Op {
+    Acc(isize),
+    Jmp(isize),
+    Nop(isize),
+    Ext(Arc<dyn Instruction>),
+}
+
+// Parses one of the core acc, jmp and nop instructions
+impl FromStr for Op {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        InstructionSet::new().parse(input)
+    }
+}
+
+impl Op {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Op::Acc(_) => "acc",
+            Op::Jmp(_) => "jmp",
+            Op::Nop(_) => "nop",
+            Op::Ext(ins) => ins.name(),
+        }
+    }
+
+    // Swaps jmp and nop; nothing else can be flipped
+    pub fn flip(&self) -> Option<Op> {
+        match self {
+            Op::Jmp(v) => Some(Op::Nop(*v)),
+            Op::Nop(v) => Some(Op::Jmp(*v)),
+            _ => None,
+        }
+    }
+
+    // Next ip after executing this op at ip, or None if it jumps before 0 or
+    // could continue at more than one place
+    pub fn successor(&self, ip: usize) -> Option<usize> {
+        match self {
+            Op::Jmp(v) => jump(ip, *v),
+            Op::Ext(ins) => match ins.successors(ip)[..] {
+                [next] => Some(next),
+                _ => None,
+            },
+            _ => Some(ip + 1),
+        }
+    }
+}
+
+impl PartialEq for Op {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Op::Acc(a), Op::Acc(b)) | (Op::Jmp(a), Op::Jmp(b)) | (Op::Nop(a), Op::Nop(b)) => {
+                a == b
+            }
+            (Op::Ext(a), Op::Ext(b)) => a.to_string() == b.to_string(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Acc(v) | Op::Jmp(v) | Op::Nop(v) => write!(f, "{} {:+}", self.name(), v),
+            Op::Ext(ins) => write!(f, "{}", ins),
+        }
+    }
+}
+
+pub type Program = Vec<Op>;
+
+// None if the jump would leave the range of usize, which is out of bounds for
+// any program
+fn jump(ip: usize, offset: isize) -> Option<usize> {
+    let abs = offset.unsigned_abs();
+    if offset > 0 {
+        ip.checked_add(abs)
+    } else {
+        ip.checked_sub(abs)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ProgramError {
+    InfiniteLoop,
+    OutOfBounds,
+    NeedsInput,
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgramError::InfiniteLoop => write!(f, "Program entered infinite loop."),
+            ProgramError::OutOfBounds => write!(f, "Program jumped out of bounds."),
+            ProgramError::NeedsInput => write!(f, "Program is waiting for input."),
+        }
+    }
+}
+impl Error for ProgramError {}
+
+// Source and sink for the in/out instructions
+pub trait Io {
+    fn input(&mut self) -> Option<isize>;
+    fn output(&mut self, value: isize);
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Queues {
+    pub input: VecDeque<isize>,
+    pub output: VecDeque<isize>,
+}
+
+impl Io for Queues {
+    fn input(&mut self) -> Option<isize> {
+        self.input.pop_front()
+    }
+    fn output(&mut self, value: isize) {
+        self.output.push_back(value);
+    }
+}
+
+// Never has input and discards output
+pub struct NoIo;
+
+impl Io for NoIo {
+    fn input(&mut self) -> Option<isize> {
+        None
+    }
+    fn output(&mut self, _value: isize) {}
+}
+
+pub const REGISTERS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reg {
+    Acc,
+    Gp(usize),
+}
+
+impl Reg {
+    fn parse(input: &str) -> Result<Self, String> {
+        match input.as_bytes() {
+            b"acc" => Ok(Reg::Acc),
+            [r @ b'a'..=b'h'] => Ok(Reg::Gp(usize::from(r - b'a'))),
+            _ => Err(format!("Invalid register {:?}.", input)),
+        }
+    }
+}
+
+impl fmt::Display for Reg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reg::Acc => write!(f, "acc"),
+            Reg::Gp(i) => write!(f, "{}", char::from(b'a' + *i as u8)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operand {
+    Reg(Reg),
+    Imm(isize),
+}
+
+impl Operand {
+    fn parse(input: &str) -> Result<Self, String> {
+        match input.parse::<isize>() {
+            Ok(v) => Ok(Operand::Imm(v)),
+            Err(_) => Reg::parse(input).map(Operand::Reg),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Reg(r) => write!(f, "{}", r),
+            Operand::Imm(v) => write!(f, "{:+}", v),
+        }
+    }
+}
+
+// An opcode outside of acc/jmp/nop. Implementors are registered with an
+// InstructionSet under their mnemonic.
+pub trait Instruction: fmt::Debug + fmt::Display + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Executes the instruction at vm.ip, leaving vm.ip at the next one to run
+    fn execute(&self, vm: &mut VirtualMachine, io: &mut dyn Io) -> Result<(), ProgramError>;
+
+    // Every ip execution can continue at, for static analysis
+    fn successors(&self, ip: usize) -> Vec<usize> {
+        vec![ip + 1]
+    }
+}
+
+pub type Parser = fn(&str) -> Result<Arc<dyn Instruction>, String>;
+
+#[derive(Clone, Default)]
+pub struct InstructionSet {
+    parsers: HashMap<&'static str, Parser>,
+}
+
+impl InstructionSet {
+    // Only the day 8 acc/jmp/nop
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Day 8 plus registers, conditional jumps and I/O
+    pub fn extended() -> Self {
+        let mut isa = Self::new();
+        isa.register("set", |args| {
+            let (dst, src) = two_args(args)?;
+            Ok(Arc::new(Set(Reg::parse(dst)?, Operand::parse(src)?)))
+        })
+        .register("add", |args| {
+            let (dst, src) = two_args(args)?;
+            Ok(Arc::new(Add(Reg::parse(dst)?, Operand::parse(src)?)))
+        })
+        .register("jz", |args| {
+            let (cond, offset) = two_args(args)?;
+            Ok(Arc::new(JumpIf {
+                zero: true,
+                cond: Operand::parse(cond)?,
+                offset: parse_offset(offset)?,
+            }))
+        })
+        .register("jnz", |args| {
+            let (cond, offset) = two_args(args)?;
+            Ok(Arc::new(JumpIf {
+                zero: false,
+                cond: Operand::parse(cond)?,
+                offset: parse_offset(offset)?,
+            }))
+        })
+        .register("in", |args| Ok(Arc::new(In(Reg::parse(args)?))))
+        .register("out", |args| Ok(Arc::new(Out(Operand::parse(args)?))));
+        isa
+    }
+
+    // Adds or replaces an opcode; acc/jmp/nop can't be overridden
+    pub fn register(&mut self, name: &'static str, parser: Parser) -> &mut Self {
+        self.parsers.insert(name, parser);
+        self
+    }
+
+    // Every mnemonic this set parses, sorted
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names = vec!["acc", "jmp", "nop"];
+        names.extend(self.parsers.keys());
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    pub fn parse(&self, line: &str) -> Result<Op, String> {
+        self.parse_line(line).map_err(|e| e.message)
+    }
+
+    // Like parse, but also says which column of line the problem is at
+    pub fn parse_line(&self, line: &str) -> Result<Op, LineError> {
+        let name_col = line.len() - line.trim_start().len() + 1;
+        let trimmed = line.trim();
+        let (name, args, args_col) = match trimmed.find(' ') {
+            Some(i) => {
+                let rest = &trimmed[i + 1..];
+                let skipped = rest.len() - rest.trim_start().len();
+                (&trimmed[..i], rest.trim(), name_col + i + 1 + skipped)
+            }
+            None => (trimmed, "", name_col + trimmed.len()),
+        };
+        let at_args = |e| LineError::new(args_col, e);
+        match name {
+            "acc" => Ok(Op::Acc(parse_offset(args).map_err(at_args)?)),
+            "jmp" => Ok(Op::Jmp(parse_offset(args).map_err(at_args)?)),
+            "nop" => Ok(Op::Nop(parse_offset(args).map_err(at_args)?)),
+            _ => match self.parsers.get(name) {
+                Some(parser) => parser(args).map(Op::Ext).map_err(at_args),
+                None => Err(LineError::new(
+                    name_col,
+                    format!("Unknown operation {:?}.", name),
+                )),
+            },
+        }
+    }
+
+    pub fn parse_program(&self, input: &str) -> Result<Program, String> {
+        input.lines().map(|line| self.parse(line)).collect()
+    }
+}
+
+fn two_args(args: &str) -> Result<(&str, &str), String> {
+    let mut parts = args.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(a), Some(b), None) => Ok((a, b)),
+        _ => Err(format!("Expected two arguments, got {:?}.", args)),
+    }
+}
+
+fn parse_offset(input: &str) -> Result<isize, String> {
+    input
+        .parse::<isize>()
+        .map_err(|_| format!("Invalid number {:?}.", input))
+}
+
+#[derive(Debug)]
+struct Set(Reg, Operand);
+
+impl Instruction for Set {
+    fn name(&self) -> &'static str {
+        "set"
+    }
+    fn execute(&self, vm: &mut VirtualMachine, _io: &mut dyn Io) -> Result<(), ProgramError> {
+        *vm.reg_mut(self.0) = vm.value(self.1);
+        vm.ip += 1;
+        Ok(())
+    }
+}
+
+impl fmt::Display for Set {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "set {} {}", self.0, self.1)
+    }
+}
+
+#[derive(Debug)]
+struct Add(Reg, Operand);
+
+impl Instruction for Add {
+    fn name(&self) -> &'static str {
+        "add"
+    }
+    fn execute(&self, vm: &mut VirtualMachine, _io: &mut dyn Io) -> Result<(), ProgramError> {
+        let value = vm.value(self.1);
+        let reg = vm.reg_mut(self.0);
+        *reg = reg.wrapping_add(value);
+        vm.ip += 1;
+        Ok(())
+    }
+}
+
+impl fmt::Display for Add {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "add {} {}", self.0, self.1)
+    }
+}
+
+#[derive(Debug)]
+struct JumpIf {
+    zero: bool,
+    cond: Operand,
+    offset: isize,
+}
+
+impl Instruction for JumpIf {
+    fn name(&self) -> &'static str {
+        if self.zero {
+            "jz"
+        } else {
+            "jnz"
+        }
+    }
+    fn execute(&self, vm: &mut VirtualMachine, _io: &mut dyn Io) -> Result<(), ProgramError> {
+        vm.ip = if (vm.value(self.cond) == 0) == self.zero {
+            jump(vm.ip, self.offset).ok_or(ProgramError::OutOfBounds)?
+        } else {
+            vm.ip + 1
+        };
+        Ok(())
+    }
+    fn successors(&self, ip: usize) -> Vec<usize> {
+        let mut next = vec![ip + 1];
+        next.extend(jump(ip, self.offset));
+        next
+    }
+}
+
+impl fmt::Display for JumpIf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {:+}", self.name(), self.cond, self.offset)
+    }
+}
+
+#[derive(Debug)]
+struct In(Reg);
+
+impl Instruction for In {
+    fn name(&self) -> &'static str {
+        "in"
+    }
+    // Leaves ip in place when there's no input, so execution can resume
+    fn execute(&self, vm: &mut VirtualMachine, io: &mut dyn Io) -> Result<(), ProgramError> {
+        *vm.reg_mut(self.0) = io.input().ok_or(ProgramError::NeedsInput)?;
+        vm.ip += 1;
+        Ok(())
+    }
+}
+
+impl fmt::Display for In {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "in {}", self.0)
+    }
+}
+
+#[derive(Debug)]
+struct Out(Operand);
+
+impl Instruction for Out {
+    fn name(&self) -> &'static str {
+        "out"
+    }
+    fn execute(&self, vm: &mut VirtualMachine, io: &mut dyn Io) -> Result<(), ProgramError> {
+        io.output(vm.value(self.0));
+        vm.ip += 1;
+        Ok(())
+    }
+}
+
+impl fmt::Display for Out {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "out {}", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Step {
+    Running,
+    Halted(isize),
+}
+
+// acc and the registers wrap on overflow like a machine's would, so no
+// program can make the VM panic
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VirtualMachine {
+    pub ip: usize,
+    pub acc: isize,
+    pub regs: [isize; REGISTERS],
+}
+
+impl VirtualMachine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reg_mut(&mut self, reg: Reg) -> &mut isize {
+        match reg {
+            Reg::Acc => &mut self.acc,
+            Reg::Gp(i) => &mut self.regs[i],
+        }
+    }
+
+    pub fn value(&self, operand: Operand) -> isize {
+        match operand {
+            Operand::Reg(Reg::Acc) => self.acc,
+            Operand::Reg(Reg::Gp(i)) => self.regs[i],
+            Operand::Imm(v) => v,
+        }
+    }
+
+    pub fn step(&mut self, prog: &[Op]) -> Result<Step, ProgramError> {
+        self.step_io(prog, &mut NoIo)
+    }
+
+    // Executes the instruction at ip; halts once ip is just past the end
+    pub fn step_io(&mut self, prog: &[Op], io: &mut dyn Io) -> Result<Step, ProgramError> {
+        if self.ip == prog.len() {
+            return Ok(Step::Halted(self.acc));
+        }
+        match prog.get(self.ip).ok_or(ProgramError::OutOfBounds)? {
+            Op::Ext(ins) => ins.execute(self, io)?,
+            op => {
+                if let Op::Acc(v) = op {
+                    self.acc = self.acc.wrapping_add(*v);
+                }
+                self.ip = op.successor(self.ip).ok_or(ProgramError::OutOfBounds)?;
+            }
+        }
+        Ok(Step::Running)
+    }
+
+    // Day 8 semantics: reaching any instruction a second time is a loop
+    pub fn run(&mut self, prog: &[Op]) -> Result<isize, ProgramError> {
+        let mut visited = HashSet::new();
+        loop {
+            if !visited.insert(self.ip) {
+                return Err(ProgramError::InfiniteLoop);
+            }
+            if let Step::Halted(acc) = self.step(prog)? {
+                return Ok(acc);
+            }
+        }
+    }
+
+    // Only a repeated ip and register state counts as a loop, which is exact
+    // as long as the program doesn't read input
+    pub fn run_io(&mut self, prog: &[Op], io: &mut dyn Io) -> Result<isize, ProgramError> {
+        let mut visited = HashSet::new();
+        loop {
+            if !visited.insert((self.ip, self.acc, self.regs)) {
+                return Err(ProgramError::InfiniteLoop);
+            }
+            if let Step::Halted(acc) = self.step_io(prog, io)? {
+                return Ok(acc);
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Repair {
+    pub ip: usize,
+    pub op: Op,
+    pub acc: isize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RepairError {
+    Terminates(isize),
+    Unrepairable(Option<Cycle>),
+}
+
+impl fmt::Display for RepairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepairError::Terminates(acc) => {
+                write!(f, "Program already terminates with acc {}.", acc)
+            }
+            RepairError::Unrepairable(None) => {
+                write!(f, "No single jmp/nop flip terminates program.")
+            }
+            RepairError::Unrepairable(Some(cycle)) => write!(
+                f,
+                "No single jmp/nop flip terminates program looping through {}.",
+                cycle
+            ),
+        }
+    }
+}
+impl Error for RepairError {}
+
+// Finds every single jmp/nop flip that makes a looping program terminate, in ip order.
+//
+// Each instruction has exactly one successor, so walking the reversed edges back from
+// the end (ip == prog.len()) marks every instruction that already terminates, along with
+// the acc it picks up on the way out. Only flips on the original execution path change
+// what runs, and such a flip works iff its new successor is one of the marked ones.
+pub fn repairs(prog: &[Op]) -> Result<Vec<Repair>, RepairError> {
+    let end = prog.len();
+    let mut preds = vec![Vec::new(); end + 1];
+    for (ip, op) in prog.iter().enumerate() {
+        match op.successor(ip) {
+            Some(next) if next <= end => preds[next].push(ip),
+            _ => (),
+        }
+    }
+
+    // acc_to_end[ip] is Some(acc gained from ip to termination) if ip terminates
+    let mut acc_to_end: Vec<Option<isize>> = vec![None; end + 1];
+    acc_to_end[end] = Some(0);
+    let mut stack = vec![end];
+    while let Some(next) = stack.pop() {
+        let acc = acc_to_end[next].unwrap();
+        for &ip in &preds[next] {
+            acc_to_end[ip] = Some(match prog[ip] {
+                Op::Acc(v) => acc.wrapping_add(v),
+                _ => acc,
+            });
+            stack.push(ip);
+        }
+    }
+
+    if let Some(acc) = acc_to_end[0] {
+        return Err(RepairError::Terminates(acc));
+    }
+
+    let mut found = Vec::new();
+    let mut visited = vec![false; end];
+    let mut vm = VirtualMachine::new();
+    while vm.ip < end && !visited[vm.ip] {
+        visited[vm.ip] = true;
+        let op = &prog[vm.ip];
+        if let Some(new_op) = op.flip() {
+            let acc_after = new_op
+                .successor(vm.ip)
+                .and_then(|next| acc_to_end.get(next).copied().flatten());
+            if let Some(acc) = acc_after {
+                found.push(Repair {
+                    ip: vm.ip,
+                    op: new_op,
+                    // Wrapping makes the order acc is summed in not matter
+                    acc: vm.acc.wrapping_add(acc),
+                });
+            }
+        }
+        if vm.step(prog).is_err() {
+            break;
+        }
+    }
+
+    if found.is_empty() {
+        return Err(RepairError::Unrepairable(explain_loop(prog)));
+    }
+    found.sort_unstable_by_key(|r| r.ip);
+    Ok(found)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Looped {
+    // Just before an instruction would run a second time
+    pub acc: isize,
+    // None if the program terminated instead
+    pub cycle: Option<Cycle>,
+}
+
+// Runs a program until it loops, along with the cycle it's stuck in. One that
+// terminates gives its final acc and no cycle.
+pub fn find_loop(prog: &[Op]) -> Result<Looped, ProgramError> {
+    let mut vm = VirtualMachine::new();
+    match vm.run(prog) {
+        Err(ProgramError::InfiniteLoop) => Ok(Looped {
+            acc: vm.acc,
+            cycle: Cfg::new(prog).cycle_at(vm.ip),
+        }),
+        Ok(acc) => Ok(Looped { acc, cycle: None }),
+        Err(e) => Err(e),
+    }
+}
+
+// The cycle a program gets stuck in, if it loops rather than terminating or
+// jumping out of bounds
+pub fn explain_loop(prog: &[Op]) -> Option<Cycle> {
+    find_loop(prog).ok()?.cycle
+}
+
+#[aoc_generator(day8)]
+pub fn input_generator(input: &str) -> Result<Program, ParseError> {
+    let isa = InstructionSet::new();
+    parse_lines(8, input, |line| isa.parse_line(line))
+}
+
+#[aoc(day8, part1)]
+fn run_once(prog: &Program) -> Result<isize, ProgramError> {
+    find_loop(prog).map(|looped| looped.acc)
+}
+
+#[aoc(day8, part2)]
+fn fix_prog(prog: &Program) -> Result<isize, RepairError> {
+    Ok(repairs(prog)?[0].acc)
+}
+
+solver!(2020, 8, input_generator, run_once, fix_prog);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const INPUT: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn test_op_display() {
+        let prog = input_generator(INPUT).unwrap();
+        assert_eq!(prog[0].to_string(), "nop +0");
+        assert_eq!(prog[4].to_string(), "jmp -3");
+        assert_eq!(prog[4].to_string().parse::<Op>(), Ok(prog[4].clone()));
+        assert!("jmp".parse::<Op>().is_err());
+    }
+
+    #[test]
+    fn test_step() {
+        let prog = input_generator(INPUT).unwrap();
+        let mut vm = VirtualMachine::new();
+        let mut trace = vec![vm.ip];
+        for _ in 0..5 {
+            assert_eq!(vm.step(&prog), Ok(Step::Running));
+            trace.push(vm.ip);
+        }
+        assert_eq!(trace, vec![0, 1, 2, 6, 7, 3]);
+        assert_eq!(vm.acc, 2);
+
+        let mut vm = VirtualMachine::new();
+        assert_eq!(vm.step(&[Op::Jmp(-1)]), Err(ProgramError::OutOfBounds));
+        assert_eq!(vm.step(&[]), Ok(Step::Halted(0)));
+    }
+
+    #[test]
+    fn test_extreme_offsets() {
+        let prog = input_generator("nop +0\njmp -9223372036854775808").unwrap();
+        assert_eq!(prog[1], Op::Jmp(isize::MIN));
+        assert_eq!(run_once(&prog), Err(ProgramError::OutOfBounds));
+        assert_eq!(fix_prog(&prog).unwrap(), 0);
+
+        let prog = vec![Op::Nop(0), Op::Jmp(isize::MAX)];
+        assert_eq!(run_once(&prog), Err(ProgramError::OutOfBounds));
+        assert_eq!(jump(1, isize::MAX), Some(isize::MAX as usize + 1));
+        assert_eq!(jump(usize::MAX, isize::MAX), None);
+        assert_eq!(jump(usize::MAX, isize::MIN), Some(isize::MAX as usize));
+        assert_eq!(jump(0, isize::MIN), None);
+
+        let prog = input_generator("acc +9223372036854775807\nacc +2\njmp +0").unwrap();
+        assert_eq!(run_once(&prog), Ok(isize::MIN + 1));
+        assert_eq!(fix_prog(&prog), Ok(isize::MIN + 1));
+        let prog = InstructionSet::extended()
+            .parse_program("set a -9223372036854775808\nadd a -1\nadd acc a")
+            .unwrap();
+        assert_eq!(VirtualMachine::new().run(&prog), Ok(isize::MAX));
+    }
+
+    #[test]
+    fn test_isa_parse() {
+        let isa = InstructionSet::extended();
+        assert_eq!(
+            isa.parse_program(INPUT),
+            Ok(input_generator(INPUT).unwrap())
+        );
+        for line in &[
+            "set a +5",
+            "add acc b",
+            "jz a -2",
+            "jnz +1 +3",
+            "in h",
+            "out c",
+        ] {
+            assert_eq!(isa.parse(line).unwrap().to_string(), *line);
+        }
+        assert!(isa.parse("set i +1").is_err());
+        assert!(isa.parse("add a").is_err());
+        assert!(InstructionSet::new().parse("out a").is_err());
+        assert_eq!(InstructionSet::new().names(), vec!["acc", "jmp", "nop"]);
+        assert_eq!(isa.names().len(), 9);
+        assert_eq!(isa.parse_line("  jz  a x").unwrap_err().column, 7);
+        assert_eq!(isa.parse_line("acc").unwrap_err().column, 4);
+        let err = input_generator("nop +0\nacc +1\nnop x\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 5));
+        assert_eq!(err.message, "Invalid number \"x\".");
+    }
+
+    #[test]
+    fn test_isa_io() {
+        // Counts down from the input, then exits with the total in acc
+        let prog = InstructionSet::extended()
+            .parse_program("in a\nout a\nadd acc a\nadd a -1\njnz a -3")
+            .unwrap();
+        let mut io = Queues::default();
+        let mut vm = VirtualMachine::new();
+        assert_eq!(vm.run_io(&prog, &mut io), Err(ProgramError::NeedsInput));
+        assert_eq!(vm.ip, 0);
+        io.input.push_back(4);
+        assert_eq!(vm.run_io(&prog, &mut io), Ok(10));
+        assert_eq!(io.output, vec![4, 3, 2, 1]);
+        assert_eq!(
+            VirtualMachine::new().run(&prog),
+            Err(ProgramError::NeedsInput)
+        );
+    }
+
+    #[derive(Debug)]
+    struct Double;
+
+    impl Instruction for Double {
+        fn name(&self) -> &'static str {
+            "dbl"
+        }
+        fn execute(&self, vm: &mut VirtualMachine, _io: &mut dyn Io) -> Result<(), ProgramError> {
+            vm.acc *= 2;
+            vm.ip += 1;
+            Ok(())
+        }
+    }
+
+    impl fmt::Display for Double {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "dbl")
+        }
+    }
+
+    #[test]
+    fn test_isa_register() {
+        let mut isa = InstructionSet::new();
+        isa.register("dbl", |_| Ok(Arc::new(Double)));
+        let prog = isa.parse_program("acc +3\ndbl\ndbl").unwrap();
+        assert_eq!(VirtualMachine::new().run(&prog), Ok(12));
+    }
+
+    #[test]
+    fn test_repairs() {
+        let prog = input_generator(INPUT).unwrap();
+        assert_eq!(
+            repairs(&prog),
+            Ok(vec![Repair {
+                ip: 7,
+                op: Op::Nop(-4),
+                acc: 8
+            }])
+        );
+        assert_eq!(
+            repairs(&input_generator("jmp +2\njmp +0\nacc +3").unwrap()),
+            Err(RepairError::Terminates(3))
+        );
+        assert_eq!(
+            repairs(&input_generator("acc +1\njmp -1\njmp -2").unwrap()),
+            Err(RepairError::Unrepairable(Some(Cycle(vec![0, 1]))))
+        );
+    }
+
+    #[test]
+    fn test_repairs_all() {
+        // Both the nop at 0 and the jmp at 1 can be flipped
+        let prog = input_generator("nop +3\njmp +0\nacc +1\nacc +2").unwrap();
+        let found = repairs(&prog).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].ip, found[0].acc), (0, 2));
+        assert_eq!((found[1].ip, found[1].acc), (1, 3));
+        for r in found {
+            let mut patched = prog.clone();
+            patched[r.ip] = r.op;
+            assert_eq!(VirtualMachine::new().run(&patched), Ok(r.acc));
+        }
+    }
+
+    #[test]
+    fn test_repairs_large() {
+        let n = 300_000;
+        let mut prog = vec![Op::Acc(1); n];
+        prog[0] = Op::Jmp(2);
+        prog[n / 2] = Op::Nop(-(n as isize));
+        prog[n - 1] = Op::Jmp(-(n as isize - 2));
+        let found = repairs(&prog).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].ip, n - 1);
+        assert_eq!(found[0].acc, n as isize - 4);
+    }
+
+    #[test]
+    fn test_parts() {
+        let prog = input_generator(INPUT).unwrap();
+        assert_eq!(run_once(&prog), Ok(5));
+        assert_eq!(
+            find_loop(&prog),
+            Ok(Looped {
+                acc: 5,
+                cycle: Some(Cycle(vec![1, 2, 6, 7, 3, 4]))
+            })
+        );
+        let done = input_generator("acc +2\nnop +0").unwrap();
+        assert_eq!(
+            find_loop(&done),
+            Ok(Looped {
+                acc: 2,
+                cycle: None
+            })
+        );
+        assert_eq!(fix_prog(&prog), Ok(8));
+    }
+
+    // Reference interpreter: Ok(acc) when it runs just past the last line,
+    // Err(Some(acc)) just before a line would run twice, Err(None) when it
+    // jumps anywhere else
+    fn naive_run(prog: &[(&str, isize)]) -> Result<isize, Option<isize>> {
+        let (mut ip, mut acc) = (0isize, 0);
+        let mut seen = vec![false; prog.len()];
+        loop {
+            if ip == prog.len() as isize {
+                return Ok(acc);
+            }
+            if ip < 0 || ip > prog.len() as isize {
+                return Err(None);
+            }
+            if seen[ip as usize] {
+                return Err(Some(acc));
+            }
+            seen[ip as usize] = true;
+            match prog[ip as usize] {
+                ("acc", v) => acc += v,
+                ("jmp", v) => ip += v - 1,
+                _ => (),
+            }
+            ip += 1;
+        }
+    }
+
+    // Reference repair: flip each jmp or nop in turn and run it
+    fn naive_fix(prog: &[(&'static str, isize)]) -> Option<isize> {
+        (0..prog.len()).find_map(|i| {
+            let mut patched = prog.to_vec();
+            patched[i].0 = match prog[i].0 {
+                "jmp" => "nop",
+                "nop" => "jmp",
+                _ => return None,
+            };
+            naive_run(&patched).ok()
+        })
+    }
+
+    fn program() -> impl Strategy<Value = Vec<(&'static str, isize)>> {
+        prop::collection::vec(
+            (
+                prop::sample::select(vec!["acc", "jmp", "nop"]),
+                -12..12isize,
+            ),
+            1..40,
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn parts_match_naive(ops in program()) {
+            let text: String = ops.iter().map(|(op, v)| format!("{} {:+}\n", op, v)).collect();
+            let prog = input_generator(&text).unwrap();
+            match naive_run(&ops) {
+                Ok(acc) => {
+                    prop_assert_eq!(run_once(&prog), Ok(acc));
+                    prop_assert_eq!(fix_prog(&prog), Err(RepairError::Terminates(acc)));
+                }
+                Err(looped) => {
+                    let part1 = looped.ok_or(ProgramError::OutOfBounds);
+                    prop_assert_eq!(run_once(&prog), part1);
+                    prop_assert_eq!(fix_prog(&prog).ok(), naive_fix(&ops));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use super::{jump, Op};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    Block(usize),
+    Exit,
+    OutOfBounds,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BasicBlock {
+    pub ips: Range<usize>,
+    pub targets: Vec<Target>,
+}
+
+// Instructions executed one after another in the order they were run, ending
+// just before the first one repeats
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cycle(pub Vec<usize>);
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ip in &self.0 {
+            write!(f, "{} -> ", ip)?;
+        }
+        match self.0.first() {
+            Some(ip) => write!(f, "{}", ip),
+            None => Ok(()),
+        }
+    }
+}
+
+// Every ip execution can continue at after op, None where it jumps before 0
+fn successors(op: &Op, ip: usize) -> Vec<Option<usize>> {
+    match op {
+        Op::Jmp(v) => vec![jump(ip, *v)],
+        Op::Ext(ins) => ins.successors(ip).into_iter().map(Some).collect(),
+        _ => vec![Some(ip + 1)],
+    }
+}
+
+#[derive(Debug)]
+pub struct Cfg<'a> {
+    prog: &'a [Op],
+    pub blocks: Vec<BasicBlock>,
+    block_of: Vec<usize>,
+    // Strongly connected component of each block, and the blocks in each component
+    component_of: Vec<usize>,
+    components: Vec<Vec<usize>>,
+}
+
+impl<'a> Cfg<'a> {
+    pub fn new(prog: &'a [Op]) -> Self {
+        let len = prog.len();
+        let mut leader = vec![false; len];
+        if len > 0 {
+            leader[0] = true;
+        }
+        for (ip, op) in prog.iter().enumerate() {
+            let next = successors(op, ip);
+            if next != [Some(ip + 1)] {
+                for t in next.into_iter().flatten().chain(Some(ip + 1)) {
+                    if t < len {
+                        leader[t] = true;
+                    }
+                }
+            }
+        }
+
+        let mut blocks: Vec<BasicBlock> = Vec::new();
+        let mut block_of = Vec::with_capacity(len);
+        for (ip, &leads) in leader.iter().enumerate() {
+            if leads {
+                blocks.push(BasicBlock {
+                    ips: ip..ip + 1,
+                    targets: Vec::new(),
+                });
+            } else {
+                blocks.last_mut().unwrap().ips.end = ip + 1;
+            }
+            block_of.push(blocks.len() - 1);
+        }
+        for block in blocks.iter_mut() {
+            let last = block.ips.end - 1;
+            block.targets = successors(&prog[last], last)
+                .into_iter()
+                .map(|t| match t {
+                    Some(t) if t < len => Target::Block(block_of[t]),
+                    Some(t) if t == len => Target::Exit,
+                    _ => Target::OutOfBounds,
+                })
+                .collect();
+        }
+
+        let (component_of, components) = tarjan(&blocks);
+        Cfg {
+            prog,
+            blocks,
+            block_of,
+            component_of,
+            components,
+        }
+    }
+
+    pub fn block_of(&self, ip: usize) -> Option<usize> {
+        self.block_of.get(ip).copied()
+    }
+
+    fn block_targets(&self, block: usize) -> impl Iterator<Item = usize> + '_ {
+        self.blocks[block].targets.iter().filter_map(|t| match t {
+            Target::Block(b) => Some(*b),
+            _ => None,
+        })
+    }
+
+    fn is_loop(&self, component: &[usize]) -> bool {
+        component.len() > 1 || self.block_targets(component[0]).any(|b| b == component[0])
+    }
+
+    // Groups of blocks that can run forever, as block ids
+    pub fn loops(&self) -> Vec<&[usize]> {
+        self.components
+            .iter()
+            .filter(|c| self.is_loop(c))
+            .map(|c| &c[..])
+            .collect()
+    }
+
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut seen = vec![false; self.blocks.len()];
+        let mut stack = Vec::new();
+        if !self.blocks.is_empty() {
+            seen[0] = true;
+            stack.push(0);
+        }
+        while let Some(b) = stack.pop() {
+            for next in self.block_targets(b) {
+                if !seen[next] {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    // Instruction ranges that no execution from ip 0 can reach
+    pub fn dead_code(&self) -> Vec<Range<usize>> {
+        let mut dead: Vec<Range<usize>> = Vec::new();
+        for (block, live) in self.blocks.iter().zip(self.reachable()) {
+            if live {
+                continue;
+            }
+            match dead.last_mut() {
+                Some(range) if range.end == block.ips.start => range.end = block.ips.end,
+                _ => dead.push(block.ips.clone()),
+            }
+        }
+        dead
+    }
+
+    // The shortest cycle through ip if it's inside a loop. A breadth-first
+    // search over the loop's instructions, since with conditional jumps the
+    // first successor can lead into an inner loop that never comes back to ip.
+    pub fn cycle_at(&self, ip: usize) -> Option<Cycle> {
+        let component = self.component_of[self.block_of(ip)?];
+        if !self.is_loop(&self.components[component]) {
+            return None;
+        }
+        let in_loop = |t: &usize| {
+            self.block_of(*t)
+                .is_some_and(|b| self.component_of[b] == component)
+        };
+
+        // Where the search first came to each ip from
+        let mut came_from = HashMap::new();
+        let mut queue = VecDeque::from(vec![ip]);
+        while let Some(cur) = queue.pop_front() {
+            for next in successors(&self.prog[cur], cur)
+                .into_iter()
+                .flatten()
+                .filter(in_loop)
+            {
+                if next == ip {
+                    let mut ips = vec![cur];
+                    while let Some(&prev) = came_from.get(ips.last().unwrap()) {
+                        ips.push(prev);
+                    }
+                    ips.reverse();
+                    return Some(Cycle(ips));
+                }
+                if let Entry::Vacant(e) = came_from.entry(next) {
+                    e.insert(cur);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    // Graphviz source; loops are shaded and dead code is dashed
+    pub fn to_dot(&self) -> String {
+        let live = self.reachable();
+        let mut looping = vec![false; self.blocks.len()];
+        for component in self.loops() {
+            for &b in component {
+                looping[b] = true;
+            }
+        }
+
+        let mut out = String::from("digraph program {\n    node [shape=box fontname=monospace];\n");
+        let mut oob = false;
+        for (b, block) in self.blocks.iter().enumerate() {
+            let label: String = block
+                .ips
+                .clone()
+                .map(|ip| format!("{}: {}\\l", ip, self.prog[ip]))
+                .collect();
+            let mut style = Vec::new();
+            if looping[b] {
+                style.push("filled");
+            }
+            if !live[b] {
+                style.push("dashed");
+            }
+            out.push_str(&format!("    b{} [label=\"{}\"", b, label));
+            if !style.is_empty() {
+                out.push_str(&format!(" style=\"{}\"", style.join(",")));
+            }
+            out.push_str("];\n");
+            for target in &block.targets {
+                let to = match target {
+                    Target::Block(t) => format!("b{}", t),
+                    Target::Exit => "exit".to_string(),
+                    Target::OutOfBounds => {
+                        oob = true;
+                        "oob".to_string()
+                    }
+                };
+                out.push_str(&format!("    b{} -> {};\n", b, to));
+            }
+        }
+        out.push_str("    exit [shape=doublecircle];\n");
+        if oob {
+            out.push_str("    oob [shape=octagon label=\"out of bounds\"];\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+// Iterative Tarjan, so long programs can't overflow the stack
+fn tarjan(blocks: &[BasicBlock]) -> (Vec<usize>, Vec<Vec<usize>>) {
+    const UNVISITED: usize = usize::MAX;
+    let n = blocks.len();
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut component_of = vec![0; n];
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    let targets = |b: usize| -> Vec<usize> {
+        blocks[b]
+            .targets
+            .iter()
+            .filter_map(|t| match t {
+                Target::Block(t) => Some(*t),
+                _ => None,
+            })
+            .collect()
+    };
+
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // (block, position in its target list)
+        let mut work = vec![(root, 0)];
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&mut (b, ref mut pos)) = work.last_mut() {
+            let next = targets(b);
+            if let Some(&t) = next.get(*pos) {
+                *pos += 1;
+                if index[t] == UNVISITED {
+                    index[t] = next_index;
+                    low[t] = next_index;
+                    next_index += 1;
+                    stack.push(t);
+                    on_stack[t] = true;
+                    work.push((t, 0));
+                } else if on_stack[t] {
+                    low[b] = low[b].min(index[t]);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[b]);
+            }
+            if low[b] == index[b] {
+                let mut component = Vec::new();
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component_of[member] = components.len();
+                    component.push(member);
+                    if member == b {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+    (component_of, components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{input_generator, InstructionSet};
+    use super::*;
+
+    const INPUT: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn test_blocks() {
+        let prog = input_generator(INPUT).unwrap();
+        let cfg = Cfg::new(&prog);
+        let ranges: Vec<_> = cfg.blocks.iter().map(|b| b.ips.clone()).collect();
+        assert_eq!(ranges, vec![0..1, 1..3, 3..5, 5..6, 6..8, 8..9]);
+        assert_eq!(cfg.blocks[1].targets, vec![Target::Block(4)]);
+        assert_eq!(cfg.blocks[5].targets, vec![Target::Exit]);
+        assert_eq!(cfg.block_of(7), Some(4));
+    }
+
+    #[test]
+    fn test_loops_and_dead_code() {
+        let prog = input_generator(INPUT).unwrap();
+        let cfg = Cfg::new(&prog);
+        assert_eq!(cfg.loops(), vec![&[1, 2, 4][..]]);
+        assert_eq!(cfg.dead_code(), vec![5..6, 8..9]);
+        assert_eq!(cfg.cycle_at(1), Some(Cycle(vec![1, 2, 6, 7, 3, 4])));
+        assert_eq!(
+            cfg.cycle_at(6).unwrap().to_string(),
+            "6 -> 7 -> 3 -> 4 -> 1 -> 2 -> 6"
+        );
+        assert_eq!(cfg.cycle_at(0), None);
+        assert_eq!(cfg.cycle_at(8), None);
+
+        let prog = input_generator("jmp +0").unwrap();
+        assert_eq!(Cfg::new(&prog).cycle_at(0), Some(Cycle(vec![0])));
+    }
+
+    #[test]
+    fn test_conditional_jumps() {
+        let prog = InstructionSet::extended()
+            .parse_program("set a +3\nadd a -1\njnz a -1\njmp -9")
+            .unwrap();
+        let cfg = Cfg::new(&prog);
+        assert_eq!(
+            cfg.blocks[1].targets,
+            vec![Target::Block(2), Target::Block(1)]
+        );
+        assert_eq!(cfg.blocks[2].targets, vec![Target::OutOfBounds]);
+        assert_eq!(cfg.cycle_at(1), Some(Cycle(vec![1, 2])));
+
+        // Carrying on past the jnz at 1 goes round the inner loop through 2
+        // forever, so the way back to 0 is through the jump to 3
+        let prog = InstructionSet::extended()
+            .parse_program("add a -1\njnz a +2\njmp -1\njmp -3")
+            .unwrap();
+        let cfg = Cfg::new(&prog);
+        assert_eq!(cfg.loops().len(), 1);
+        assert_eq!(cfg.cycle_at(0), Some(Cycle(vec![0, 1, 3])));
+        assert_eq!(cfg.cycle_at(2), Some(Cycle(vec![2, 1])));
+        assert_eq!(cfg.cycle_at(3), Some(Cycle(vec![3, 0, 1])));
+    }
+
+    #[test]
+    fn test_dot() {
+        let prog = input_generator("nop +0\njmp -2").unwrap();
+        let dot = Cfg::new(&prog).to_dot();
+        assert!(dot.starts_with("digraph program {\n"));
+        assert!(dot.contains("    b0 [label=\"0: nop +0\\l1: jmp -2\\l\"];\n"));
+        assert!(dot.contains("    b0 -> oob;\n"));
+        assert!(dot.contains("    oob [shape=octagon"));
+    }
+
+    #[test]
+    fn test_large() {
+        let n = 200_000;
+        let mut prog = vec![Op::Nop(0); n];
+        prog[n - 1] = Op::Jmp(-(n as isize - 1));
+        let cfg = Cfg::new(&prog);
+        assert_eq!(cfg.loops().len(), 1);
+        assert_eq!(cfg.cycle_at(0).unwrap().0.len(), n);
+    }
+}
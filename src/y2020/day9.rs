@@ -0,0 +1,350 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead};
+
+use crate::parse::{number, parse_lines, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+
+pub const PREAMBLE: usize = 25;
+
+// The last `size` numbers along with every sum of two of them, updated in O(size)
+// per number rather than recomputing all pairs
+#[derive(Debug)]
+pub struct SumWindow {
+    size: usize,
+    nums: VecDeque<usize>,
+    sums: HashMap<usize, usize>,
+}
+
+impl SumWindow {
+    pub fn new(size: usize) -> Self {
+        SumWindow {
+            size,
+            nums: VecDeque::with_capacity(size),
+            sums: HashMap::new(),
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.nums.len() == self.size
+    }
+
+    pub fn is_sum(&self, value: usize) -> bool {
+        self.sums.contains_key(&value)
+    }
+
+    // Adds value, dropping the oldest number once the window is full
+    pub fn push(&mut self, value: usize) {
+        if self.size == 0 {
+            return;
+        }
+        if self.is_full() {
+            let old = self.nums.pop_front().unwrap();
+            for sum in self.nums.iter().filter_map(|&x| old.checked_add(x)) {
+                let count = self.sums.get_mut(&sum).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    self.sums.remove(&sum);
+                }
+            }
+        }
+        // A sum too big for usize can't match any number, so it's left out
+        for sum in self.nums.iter().filter_map(|&x| value.checked_add(x)) {
+            *self.sums.entry(sum).or_insert(0) += 1;
+        }
+        self.nums.push_back(value);
+    }
+}
+
+// First number that isn't a sum of two of the `preamble` numbers before it
+pub fn find_invalid(input: &[usize], preamble: usize) -> Option<usize> {
+    let mut window = SumWindow::new(preamble);
+    for &num in input {
+        if window.is_full() && !window.is_sum(num) {
+            return Some(num);
+        }
+        window.push(num);
+    }
+    None
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Invalid {
+    pub index: usize,
+    pub value: usize,
+}
+
+// Checks numbers one line at a time, yielding each invalid one as it's read.
+// Only the window and the current line are held in memory.
+pub struct XmasStream<R> {
+    reader: R,
+    line: String,
+    index: usize,
+    window: SumWindow,
+}
+
+impl<R: BufRead> XmasStream<R> {
+    pub fn new(reader: R, preamble: usize) -> Self {
+        XmasStream {
+            reader,
+            line: String::new(),
+            index: 0,
+            window: SumWindow::new(preamble),
+        }
+    }
+
+    fn next_number(&mut self) -> io::Result<Option<usize>> {
+        loop {
+            self.line.clear();
+            if self.reader.read_line(&mut self.line)? == 0 {
+                return Ok(None);
+            }
+            let line = self.line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            return line.parse::<usize>().map(Some).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("number {}: {:?}: {}", self.index, line, e),
+                )
+            });
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for XmasStream<R> {
+    type Item = io::Result<Invalid>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let value = match self.next_number() {
+                Ok(Some(value)) => value,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            let index = self.index;
+            let invalid = self.window.is_full() && !self.window.is_sum(value);
+            self.window.push(value);
+            self.index += 1;
+            if invalid {
+                return Some(Ok(Invalid { index, value }));
+            }
+        }
+    }
+}
+
+// Start and end (inclusive) of the first contiguous run of at least two numbers
+// that adds up to the invalid number
+pub fn find_run(input: &[usize], preamble: usize) -> Option<(usize, usize)> {
+    let invalid = find_invalid(input, preamble)?;
+    let mut start = 0;
+    // Wide enough that a run of numbers near usize::MAX can't overflow it
+    let mut sum: u128 = 0;
+    for (end, &num) in input.iter().enumerate() {
+        sum += num as u128;
+        while sum > invalid as u128 && start <= end {
+            sum -= input[start] as u128;
+            start += 1;
+        }
+        if sum == invalid as u128 && end > start {
+            return Some((start, end));
+        }
+    }
+    None
+}
+
+// Sum of the smallest and largest numbers in the run find_run finds
+pub fn find_weakness(input: &[usize], preamble: usize) -> Option<usize> {
+    let (start, end) = find_run(input, preamble)?;
+    let set = &input[start..=end];
+    Some(set.iter().min()? + set.iter().max()?)
+}
+
+#[aoc_generator(day9)]
+fn input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse_lines(9, input, |line| number(line, 1))
+}
+
+#[aoc(day9, part1)]
+fn find_num(input: &[usize]) -> Option<usize> {
+    find_invalid(input, PREAMBLE)
+}
+
+#[aoc(day9, part2)]
+fn find_contiguous_set(input: &[usize]) -> Option<usize> {
+    find_weakness(input, PREAMBLE)
+}
+
+solver!(2020, 9, input_generator, find_num, find_contiguous_set);
+
+#[cfg(test)]
+mod tests {
+    use super::{find_invalid, find_run, find_weakness, Invalid, SumWindow, XmasStream};
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+    use std::io::{BufReader, Cursor, Read};
+
+    // Reference check of value against the numbers before it, rebuilding
+    // every pair from scratch
+    fn naive_is_sum(value: usize, preamble: &[usize]) -> bool {
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for &x in preamble {
+            *counts.entry(x).or_insert(0) += 1;
+        }
+        preamble.iter().any(|&x| match value.checked_sub(x) {
+            Some(y) if y == x => counts[&x] > 1,
+            Some(y) => counts.contains_key(&y),
+            None => false,
+        })
+    }
+
+    const NUMS: [usize; 20] = [
+        35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
+    ];
+
+    #[test]
+    fn test_validate() {
+        // Every number after the preamble but 127 is a sum of two of the five
+        // before it
+        let mut window = SumWindow::new(5);
+        for (i, &num) in NUMS.iter().enumerate() {
+            if i >= 5 {
+                assert_eq!(window.is_sum(num), num != 127, "{}", num);
+            }
+            window.push(num);
+        }
+        assert_eq!(find_invalid(&[5, 5, 10], 2), None);
+        assert_eq!(find_invalid(&[5, 4, 10], 2), Some(10));
+    }
+
+    #[test]
+    fn test_window() {
+        let mut window = SumWindow::new(3);
+        for &num in &[1, 2, 3, 4] {
+            window.push(num);
+        }
+        assert!(window.is_full());
+        assert!(!window.is_sum(3));
+        assert!(!window.is_sum(4));
+        assert!(window.is_sum(5));
+        assert!(window.is_sum(7));
+
+        let mut window = SumWindow::new(2);
+        window.push(5);
+        window.push(5);
+        assert!(window.is_sum(10));
+        window.push(1);
+        assert!(!window.is_sum(10));
+
+        let mut window = SumWindow::new(2);
+        for &num in &[usize::MAX, usize::MAX - 1, 1] {
+            window.push(num);
+        }
+        assert!(window.is_sum(usize::MAX));
+        window.push(usize::MAX);
+        assert!(!window.is_sum(usize::MAX));
+        assert!(!window.is_sum(0));
+    }
+
+    proptest! {
+        #[test]
+        fn window_matches_naive(
+            size in 1..6usize,
+            nums in prop::collection::vec(
+                prop_oneof![0..40usize, (usize::MAX - 40)..=usize::MAX],
+                0..60,
+            ),
+        ) {
+            let mut window = SumWindow::new(size);
+            for (i, &num) in nums.iter().enumerate() {
+                if i >= size {
+                    let expected = naive_is_sum(num, &nums[i - size..i]);
+                    prop_assert_eq!(window.is_sum(num), expected);
+                }
+                window.push(num);
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_invalid() {
+        assert_eq!(find_invalid(&NUMS, 5), Some(127));
+        assert_eq!(find_invalid(&NUMS, 2), Some(15));
+        assert_eq!(find_invalid(&NUMS[..14], 5), None);
+    }
+
+    #[test]
+    fn test_find_weakness() {
+        assert_eq!(find_run(&NUMS, 5), Some((2, 5)));
+        assert_eq!(find_weakness(&NUMS, 5), Some(62));
+        let big = [
+            1,
+            2,
+            usize::MAX - 1,
+            usize::MAX / 2,
+            usize::MAX / 2,
+            usize::MAX,
+        ];
+        assert_eq!(find_weakness(&big, 2), Some(usize::MAX - 1));
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut text: String = NUMS.iter().map(|n| format!("{}\n", n)).collect();
+        text.push_str("1\n577\n");
+        let found: Vec<_> = XmasStream::new(Cursor::new(text), 5)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                Invalid {
+                    index: 14,
+                    value: 127
+                },
+                Invalid {
+                    index: 20,
+                    value: 1
+                },
+            ]
+        );
+
+        let mut stream = XmasStream::new(Cursor::new("1\n2\nx\n3\n"), 1);
+        assert_eq!(stream.next().unwrap().unwrap().index, 1);
+        assert!(stream.next().unwrap().is_err());
+        assert_eq!(stream.next().unwrap().unwrap().value, 3);
+        assert!(stream.next().is_none());
+    }
+
+    // Yields 1, 2, 3, ... forever without ever materialising the whole input
+    struct Counting {
+        next: usize,
+        buf: Vec<u8>,
+    }
+
+    impl Read for Counting {
+        fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+            if self.buf.is_empty() {
+                self.next += 1;
+                self.buf = format!("{}\n", self.next).into_bytes();
+            }
+            let n = out.len().min(self.buf.len());
+            out[..n].copy_from_slice(&self.buf[..n]);
+            self.buf.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_stream_unbounded() {
+        // With a window of 2, n is never the sum of n - 1 and n - 2 past 3
+        let reader = BufReader::new(Counting {
+            next: 0,
+            buf: Vec::new(),
+        });
+        let stream = XmasStream::new(reader, 2);
+        let found: Vec<_> = stream.take(100_000).map(|r| r.unwrap().value).collect();
+        assert_eq!(found[..3], [4, 5, 6]);
+        assert_eq!(found.len(), 100_000);
+    }
+}
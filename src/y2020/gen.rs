@@ -0,0 +1,793 @@
+// Input generators for each day, with the answers they were built to have.
+// Where an answer can't simply be planted it's counted the obvious way while
+// the input is made, never with the solver's own code.
+use crate::answers::Answers;
+use crate::gen::{Rng, Sample};
+use crate::solver::Part;
+use crate::y2020::day11::MAX_GENERATIONS;
+use crate::y2020::day9::PREAMBLE;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+// An input and its part 1 and part 2 answers
+type Built = (String, String, String);
+
+pub fn sample(day: u32, size: usize, rng: &mut Rng) -> Option<Sample> {
+    let (input, part1, part2) = match day {
+        1 => expenses(size, rng),
+        2 => passwords(size, rng),
+        3 => trees(size, rng),
+        4 => passports(size, rng),
+        5 => passes(size, rng),
+        6 => questions(size, rng),
+        7 => bags(size, rng),
+        8 => program(size, rng),
+        9 => xmas(size, rng),
+        10 => adaptors(size, rng),
+        11 => seats(size, rng),
+        _ => return None,
+    };
+    let mut answers = Answers::default();
+    answers.insert(day, Part::One, part1);
+    answers.insert(day, Part::Two, part2);
+    Some(Sample { input, answers })
+}
+
+fn lines<T: ToString>(items: impl IntoIterator<Item = T>) -> String {
+    items.into_iter().map(|i| i.to_string() + "\n").collect()
+}
+
+fn lowercase(rng: &mut Rng) -> char {
+    (b'a' + rng.range(0..26) as u8) as char
+}
+
+// How many ways to pick k of values, by position, that add up to total
+fn count_sums(values: &[usize], k: usize, total: usize) -> usize {
+    match values.split_first() {
+        _ if k == 0 => (total == 0) as usize,
+        None => 0,
+        Some((&v, rest)) if v <= total => {
+            count_sums(rest, k, total) + count_sums(rest, k - 1, total - v)
+        }
+        Some((_, rest)) => count_sums(rest, k, total),
+    }
+}
+
+// size entries below 2020, like the real ones, with exactly one pair and one
+// triple adding up to 2020. Everything else is from 1011 up, so no two of
+// those can be in a sum together and only a few values need leaving out.
+fn expenses(size: usize, rng: &mut Rng) -> Built {
+    let planted = loop {
+        let a = rng.range(1..1010);
+        let (c, d) = (rng.range(1..600), rng.range(1..600));
+        let planted = [a, 2020 - a, c, d, 2020 - c - d];
+        if count_sums(&planted, 2, 2020) == 1 && count_sums(&planted, 3, 2020) == 1 {
+            break planted;
+        }
+    };
+    let mut excluded = HashSet::new();
+    for (i, &x) in planted.iter().enumerate() {
+        excluded.insert(2020 - x);
+        for &y in &planted[i + 1..] {
+            excluded.extend(2020usize.checked_sub(x + y));
+        }
+    }
+    let mut entries = planted.to_vec();
+    while entries.len() < size {
+        let x = rng.range(1011..2021);
+        if !excluded.contains(&x) {
+            entries.push(x);
+        }
+    }
+    rng.shuffle(&mut entries);
+    let [a, b, c, d, e] = planted;
+    (lines(entries), (a * b).to_string(), (c * d * e).to_string())
+}
+
+// size random policies, with the policy's letter common enough in each
+// password that either rule can go both ways
+fn passwords(size: usize, rng: &mut Rng) -> Built {
+    let (mut input, mut by_count, mut by_position) = (String::new(), 0, 0);
+    for _ in 0..size.max(1) {
+        let min = rng.range(1..6);
+        let max = rng.range(min + 1..min + 10);
+        let letter = lowercase(rng);
+        let length = rng.range(max..max + 6);
+        let password: Vec<char> = (0..length)
+            .map(|_| match rng.percent(40) {
+                true => letter,
+                false => lowercase(rng),
+            })
+            .collect();
+        let count = password.iter().filter(|&&c| c == letter).count();
+        if (min..=max).contains(&count) {
+            by_count += 1;
+        }
+        if (password[min - 1] == letter) != (password[max - 1] == letter) {
+            by_position += 1;
+        }
+        let password: String = password.into_iter().collect();
+        writeln!(input, "{}-{} {}: {}", min, max, letter, password).unwrap();
+    }
+    (input, by_count.to_string(), by_position.to_string())
+}
+
+// size rows of the usual 31 columns, about a quarter of them trees
+fn trees(size: usize, rng: &mut Rng) -> Built {
+    const WIDTH: usize = 31;
+    let rows: Vec<Vec<bool>> = (0..size.max(1))
+        .map(|_| (0..WIDTH).map(|_| rng.percent(25)).collect())
+        .collect();
+    let count = |down: usize, right: usize| {
+        (0..rows.len())
+            .step_by(down)
+            .enumerate()
+            .filter(|&(i, row)| rows[row][i * right % WIDTH])
+            .count()
+    };
+    let slopes = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
+    let product: usize = slopes
+        .iter()
+        .map(|&(down, right)| count(down, right))
+        .product();
+    let input = lines(rows.iter().map(|row| {
+        row.iter()
+            .map(|&tree| if tree { '#' } else { '.' })
+            .collect::<String>()
+    }));
+    (input, count(1, 3).to_string(), product.to_string())
+}
+
+const EYE_COLOURS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+// A value for a required field that parses but fails the field's check
+fn invalid_field(name: &str, rng: &mut Rng) -> String {
+    match name {
+        "byr" => rng.range(1900..1920).to_string(),
+        "iyr" => rng.range(2021..2030).to_string(),
+        "eyr" => rng.range(2010..2020).to_string(),
+        "hgt" => match rng.range(0..3) {
+            0 => format!("{}cm", rng.range(194..220)),
+            1 => format!("{}in", rng.range(40..59)),
+            _ => rng.range(150..194).to_string(),
+        },
+        "hcl" => match rng.percent(50) {
+            true => format!("#{:05x}", rng.range(0..1 << 20)),
+            false => format!("{:06x}", rng.range(0..1 << 24)),
+        },
+        "ecl" => rng.pick(&["gmt", "xry", "zzz"]).to_string(),
+        "pid" => format!("{:010}", rng.range(0..1_000_000_000)),
+        _ => unreachable!("{} is not a required field", name),
+    }
+}
+
+// size passports: a third missing a required field, a third with one field
+// failing its check and the rest valid. Any of them might have a cid, and
+// fields are spread over one or more lines.
+fn passports(size: usize, rng: &mut Rng) -> Built {
+    let (mut input, mut filled, mut valid) = (String::new(), 0, 0);
+    for i in 0..size.max(1) {
+        let height = match rng.percent(50) {
+            true => format!("{}cm", rng.range(150..194)),
+            false => format!("{}in", rng.range(59..77)),
+        };
+        let mut fields = vec![
+            ("byr", rng.range(1920..2003).to_string()),
+            ("iyr", rng.range(2010..2021).to_string()),
+            ("eyr", rng.range(2020..2031).to_string()),
+            ("hgt", height),
+            ("hcl", format!("#{:06x}", rng.range(0..1 << 24))),
+            ("ecl", rng.pick(&EYE_COLOURS).to_string()),
+            ("pid", format!("{:09}", rng.range(0..1_000_000_000))),
+        ];
+        let broken = rng.range(0..fields.len());
+        match rng.range(0..3) {
+            0 => {
+                fields.remove(broken);
+            }
+            1 => {
+                fields[broken].1 = invalid_field(fields[broken].0, rng);
+                filled += 1;
+            }
+            _ => {
+                filled += 1;
+                valid += 1;
+            }
+        }
+        if rng.percent(50) {
+            fields.push(("cid", rng.range(100..350).to_string()));
+        }
+        rng.shuffle(&mut fields);
+
+        if i > 0 {
+            input.push('\n');
+        }
+        for (j, (name, value)) in fields.iter().enumerate() {
+            if j > 0 {
+                input.push(if rng.percent(25) { '\n' } else { ' ' });
+            }
+            write!(input, "{}:{}", name, value).unwrap();
+        }
+        input.push('\n');
+    }
+    (input, filled.to_string(), valid.to_string())
+}
+
+// size boarding passes with consecutive ids but for one gap. There are only
+// 1024 seats, so there are at most 1023 passes.
+fn passes(size: usize, rng: &mut Rng) -> Built {
+    let count = size.clamp(2, 1023);
+    let first = rng.range(0..1024 - count);
+    let missing = rng.range(first + 1..first + count);
+    let mut ids: Vec<usize> = (first..=first + count)
+        .filter(|&id| id != missing)
+        .collect();
+    rng.shuffle(&mut ids);
+    let encode = |id: usize| -> String {
+        (0..10)
+            .rev()
+            .map(|bit| match (bit >= 3, id >> bit & 1 == 1) {
+                (true, false) => 'F',
+                (true, true) => 'B',
+                (false, false) => 'L',
+                (false, true) => 'R',
+            })
+            .collect()
+    };
+    let input = lines(ids.into_iter().map(encode));
+    (input, (first + count).to_string(), missing.to_string())
+}
+
+// size groups of one to five people, each answering some of the questions
+// put to their group
+fn questions(size: usize, rng: &mut Rng) -> Built {
+    let (mut input, mut anyone, mut everyone) = (String::new(), 0, 0);
+    for i in 0..size.max(1) {
+        let mut asked: Vec<char> = ('a'..='z').collect();
+        rng.shuffle(&mut asked);
+        asked.truncate(rng.range(1..27));
+        let mut union = BTreeSet::new();
+        let mut intersection: Option<BTreeSet<char>> = None;
+        if i > 0 {
+            input.push('\n');
+        }
+        for _ in 0..rng.range(1..6) {
+            let answered: BTreeSet<char> = loop {
+                let answered: BTreeSet<char> =
+                    asked.iter().filter(|_| rng.percent(60)).copied().collect();
+                if !answered.is_empty() {
+                    break answered;
+                }
+            };
+            let mut line: Vec<char> = answered.iter().copied().collect();
+            rng.shuffle(&mut line);
+            input.extend(line);
+            input.push('\n');
+            union.extend(answered.iter().copied());
+            intersection = Some(match intersection {
+                Some(common) => &common & &answered,
+                None => answered,
+            });
+        }
+        anyone += union.len();
+        everyone += intersection.map_or(0, |common| common.len());
+    }
+    (input, anyone.to_string(), everyone.to_string())
+}
+
+// No adjective is the start of another, so adding a tag to make more names
+// can't collide. "shiny" is left out so shiny gold is only ever the one bag.
+const ADJECTIVES: [&str; 17] = [
+    "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+    "muted", "pale", "plaid", "posh", "striped", "vibrant", "wavy",
+];
+const COLOURS: [&str; 33] = [
+    "aqua",
+    "beige",
+    "black",
+    "blue",
+    "bronze",
+    "brown",
+    "chartreuse",
+    "coral",
+    "crimson",
+    "cyan",
+    "fuchsia",
+    "gold",
+    "gray",
+    "green",
+    "indigo",
+    "lavender",
+    "lime",
+    "magenta",
+    "maroon",
+    "olive",
+    "orange",
+    "plum",
+    "purple",
+    "red",
+    "salmon",
+    "silver",
+    "tan",
+    "teal",
+    "tomato",
+    "turquoise",
+    "violet",
+    "white",
+    "yellow",
+];
+
+// A distinct two word colour for each n, once the plain ones run out by
+// tagging the adjective with letters
+fn colour_name(n: usize) -> String {
+    let mut adjective = ADJECTIVES[n % ADJECTIVES.len()].to_string();
+    let colour = COLOURS[n / ADJECTIVES.len() % COLOURS.len()];
+    let mut tag = n / (ADJECTIVES.len() * COLOURS.len());
+    while tag > 0 {
+        adjective.push((b'a' + (tag % 26) as u8) as char);
+        tag /= 26;
+    }
+    format!("{} {}", adjective, colour)
+}
+
+// Rules for size colours split into layers, where a bag holds up to four
+// kinds of bag from deeper layers. Keeping it shallow keeps the number of
+// bags inside any one bag small however many colours there are.
+fn bags(size: usize, rng: &mut Rng) -> Built {
+    const LAYERS: usize = 7;
+    let n = size.max(LAYERS);
+    let start = |layer: usize| (layer * n).div_ceil(LAYERS);
+    let gold = rng.range(start(LAYERS / 2)..start(LAYERS / 2 + 1));
+    let names: Vec<String> = (0..n)
+        .map(|i| match i {
+            _ if i == gold => "shiny gold".to_string(),
+            _ => colour_name(i),
+        })
+        .collect();
+
+    let mut contents: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    for (i, inside) in contents.iter_mut().enumerate() {
+        let deeper = start(i * LAYERS / n + 1)..n;
+        if deeper.is_empty() {
+            continue;
+        }
+        let kinds = rng.range(0..5).min(deeper.len());
+        let mut chosen = BTreeSet::new();
+        while chosen.len() < kinds {
+            chosen.insert(rng.range(deeper.clone()));
+        }
+        inside.extend(chosen.into_iter().map(|j| (rng.range(1..6), j)));
+    }
+
+    // Bags only hold bags later in the list, so working backwards each
+    // total is ready by the time something holds it
+    let mut total = vec![0; n];
+    for i in (0..n).rev() {
+        total[i] = contents[i]
+            .iter()
+            .map(|&(count, j)| count * (1 + total[j]))
+            .sum();
+    }
+    let mut holders = vec![Vec::new(); n];
+    for (i, inside) in contents.iter().enumerate() {
+        for &(_, j) in inside {
+            holders[j].push(i);
+        }
+    }
+    let mut seen = HashSet::new();
+    let mut stack = vec![gold];
+    while let Some(j) = stack.pop() {
+        for &i in &holders[j] {
+            if seen.insert(i) {
+                stack.push(i);
+            }
+        }
+    }
+
+    let mut rules: Vec<String> = (0..n)
+        .map(|i| {
+            let inside: Vec<String> = contents[i]
+                .iter()
+                .map(|&(count, j)| match count {
+                    1 => format!("1 {} bag", names[j]),
+                    _ => format!("{} {} bags", count, names[j]),
+                })
+                .collect();
+            match inside.is_empty() {
+                true => format!("{} bags contain no other bags.", names[i]),
+                false => format!("{} bags contain {}.", names[i], inside.join(", ")),
+            }
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+    (
+        lines(rules),
+        seen.len().to_string(),
+        total[gold].to_string(),
+    )
+}
+
+// A looping program of about size instructions where flipping exactly one
+// nop makes it finish. The path through it runs straight down, jumping over
+// blocks it never runs, then jumps back to the top. After that is a jmp
+// guarding a tail that only the fix reaches: flipping the last jmp just
+// falls into the guard, and every other nop points somewhere before the tail.
+fn program(size: usize, rng: &mut Rng) -> Built {
+    let tail_length = rng.range(1..size / 10 + 2);
+    let body = size.saturating_sub(tail_length + 2).max(2);
+    let mut prog: Vec<(&str, isize)> = Vec::new();
+    let mut acc = 0;
+    // Each nop on the path, with the acc when it runs
+    let mut nops = Vec::new();
+    while prog.len() < body {
+        match rng.range(0..10) {
+            0..=4 => {
+                let value = rng.signed(-50..51);
+                prog.push(("acc", value));
+                acc += value;
+            }
+            5..=6 => {
+                nops.push((prog.len(), acc));
+                prog.push(("nop", 0));
+            }
+            _ => {
+                let skip = rng.range(1..4);
+                prog.push(("jmp", skip as isize + 1));
+                for _ in 0..skip {
+                    let op = if rng.percent(50) { "acc" } else { "nop" };
+                    prog.push((op, rng.signed(-50..51)));
+                }
+            }
+        }
+    }
+    if nops.is_empty() {
+        nops.push((prog.len(), acc));
+        prog.push(("nop", 0));
+    }
+    let back = prog.len() as isize;
+    prog.push(("jmp", -back));
+    prog.push(("jmp", -back - 1));
+
+    let tail = prog.len();
+    let mut tail_acc = 0;
+    for _ in 0..tail_length {
+        let value = rng.signed(-50..51);
+        if rng.percent(70) {
+            prog.push(("acc", value));
+            tail_acc += value;
+        } else {
+            prog.push(("nop", value));
+        }
+    }
+
+    let &(fix, acc_at_fix) = rng.pick(&nops);
+    for &(ip, _) in &nops {
+        prog[ip].1 = match ip {
+            _ if ip == fix => (tail - ip) as isize,
+            _ => rng.signed(-(ip as isize)..(tail - ip) as isize),
+        };
+    }
+    let input = lines(prog.iter().map(|(op, arg)| format!("{} {:+}", op, arg)));
+    (input, acc.to_string(), (acc_at_fix + tail_acc).to_string())
+}
+
+// Contiguous runs of at least two numbers adding up to target, as (start, end)
+fn runs(nums: &[usize], target: usize) -> Vec<(usize, usize)> {
+    let mut found = Vec::new();
+    let (mut start, mut sum) = (0, 0);
+    for (end, &num) in nums.iter().enumerate() {
+        sum += num;
+        while sum > target {
+            sum -= nums[start];
+            start += 1;
+        }
+        if sum == target && end > start {
+            found.push((start, end));
+        }
+    }
+    found
+}
+
+// Numbers at least double every 25 lines, so valid ones stop well before
+// they could overflow
+const XMAS_LIMIT: usize = 1 << 48;
+
+// size numbers, each the sum of two of the 25 before it up to the invalid
+// one, which is the sum of a contiguous run somewhere earlier. Past five
+// hundred or so numbers the rest is random padding after the invalid one,
+// checked not to add up to it anywhere else.
+fn xmas(size: usize, rng: &mut Rng) -> Built {
+    let size = size.max(PREAMBLE + 2);
+    loop {
+        let mut nums: Vec<usize> = (1..=2 * PREAMBLE).collect();
+        rng.shuffle(&mut nums);
+        nums.truncate(PREAMBLE);
+        while nums.len() < size - 1 {
+            let window = &nums[nums.len() - PREAMBLE..];
+            let (a, b) = (*rng.pick(window), *rng.pick(window));
+            if a == b {
+                continue;
+            }
+            if a + b > XMAS_LIMIT {
+                break;
+            }
+            nums.push(a + b);
+        }
+
+        let window = &nums[nums.len() - PREAMBLE..];
+        let is_sum =
+            |n: usize| (0..PREAMBLE).any(|i| (i + 1..PREAMBLE).any(|j| window[i] + window[j] == n));
+        let start = rng.range(0..nums.len() - 1);
+        let end = rng.range(start + 1..nums.len().min(start + 16));
+        let invalid: usize = nums[start..=end].iter().sum();
+        if is_sum(invalid) {
+            continue;
+        }
+        let run = &nums[start..=end];
+        let weakness = run.iter().min().unwrap() + run.iter().max().unwrap();
+        nums.push(invalid);
+        while nums.len() < size {
+            nums.push(rng.range(1..XMAS_LIMIT));
+        }
+        if runs(&nums, invalid) == [(start, end)] {
+            return (lines(nums), invalid.to_string(), weakness.to_string());
+        }
+    }
+}
+
+// Adaptors in runs one jolt apart with gaps of three between the runs, like
+// the real input, until there are at least size. Both ends of a run are
+// fixed, and a run of k one jolt gaps can be crossed tribonacci(k) ways, so
+// the arrangements are the product over the runs. Runs are kept trivial
+// once that would overflow.
+fn adaptors(size: usize, rng: &mut Rng) -> Built {
+    const WAYS: [u128; 5] = [1, 1, 2, 4, 7];
+    let mut jolts = Vec::new();
+    let (mut last, mut ones, mut threes, mut ways) = (0, 0, 0, 1u128);
+    while jolts.len() < size.max(1) {
+        if !jolts.is_empty() {
+            last += 3;
+            jolts.push(last);
+            threes += 1;
+        }
+        let mut run = rng.range(0..WAYS.len());
+        match ways.checked_mul(WAYS[run]) {
+            Some(more) => ways = more,
+            None => run = 1,
+        }
+        for _ in 0..run {
+            last += 1;
+            jolts.push(last);
+            ones += 1;
+        }
+    }
+    rng.shuffle(&mut jolts);
+    (
+        lines(jolts),
+        (ones * (threes + 1)).to_string(),
+        ways.to_string(),
+    )
+}
+
+// Half the maps are dense like the real ones, which run the seating rules for
+// many generations, and half sparse, which only stress finding what a seat
+// sees
+fn seats(size: usize, rng: &mut Rng) -> Built {
+    if rng.percent(50) {
+        dense_seats(size, rng)
+    } else {
+        sparse_seats(size, rng)
+    }
+}
+
+// A map about three quarters seats, as wide as the real ones at most, settled
+// by simply stepping the whole map until nothing changes. Some maps never
+// settle, and the solver rejects those, so draw a few times and fall back to
+// a sparse map, which always does.
+fn dense_seats(size: usize, rng: &mut Rng) -> Built {
+    let (rows, cols) = (size.max(1), size.clamp(1, 100));
+    for _ in 0..3 {
+        let map: Vec<Vec<char>> = (0..rows)
+            .map(|_| {
+                (0..cols)
+                    .map(|_| if rng.percent(75) { 'L' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        if let (Some(part1), Some(part2)) = (settle_seats(&map, 1, 4), settle_seats(&map, rows, 5))
+        {
+            let input = lines(map.iter().map(|row| row.iter().collect::<String>()));
+            return (input, part1.to_string(), part2.to_string());
+        }
+    }
+    sparse_seats(size, rng)
+}
+
+// Occupied seats once nothing changes, where a seat looks up to reach cells
+// in each direction and empties once crowd of the seats it sees are taken.
+// What each seat sees is found once up front, then the whole map is stepped
+// until it stops changing. None if it flips back to the layout from two
+// generations ago, or hasn't settled within the solver's budget.
+fn settle_seats(map: &[Vec<char>], reach: usize, crowd: usize) -> Option<usize> {
+    let (rows, cols) = (map.len() as isize, map[0].len() as isize);
+    let mut seen = vec![Vec::new(); map.len() * map[0].len()];
+    for r in 0..rows {
+        for c in 0..cols {
+            for &(dr, dc) in &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ] {
+                let (mut y, mut x) = (r + dr, c + dc);
+                for _ in 0..reach {
+                    if y < 0 || x < 0 || y >= rows || x >= cols {
+                        break;
+                    }
+                    if map[y as usize][x as usize] == 'L' {
+                        seen[(r * cols + c) as usize].push((y * cols + x) as usize);
+                        break;
+                    }
+                    y += dr;
+                    x += dc;
+                }
+            }
+        }
+    }
+    let mut cells: Vec<char> = map.iter().flatten().copied().collect();
+    let mut before = Vec::new();
+    for _ in 0..MAX_GENERATIONS - 1 {
+        let next: Vec<char> = cells
+            .iter()
+            .zip(&seen)
+            .map(|(&cell, seen)| {
+                let taken = seen.iter().filter(|&&i| cells[i] == '#').count();
+                match cell {
+                    'L' if taken == 0 => '#',
+                    '#' if taken >= crowd => 'L',
+                    cell => cell,
+                }
+            })
+            .collect();
+        if next == cells {
+            return Some(cells.iter().filter(|&&c| c == '#').count());
+        }
+        if next == before {
+            return None;
+        }
+        before = std::mem::replace(&mut cells, next);
+    }
+    None
+}
+
+// A size by size map whose seats are all taken in the end under both rules.
+// No two seats are next to each other, and no two share a column or a
+// diagonal, so a seat can only ever see the ones either side along its row.
+// It's nearly all floor, which is the slow case for finding what a seat sees,
+// but every seat just fills in the first generation, so it doesn't exercise
+// the rules themselves.
+fn sparse_seats(size: usize, rng: &mut Rng) -> Built {
+    let side = size.max(1);
+    let mut map = vec![vec!['.'; side]; side];
+    let even: Vec<usize> = (0..side).step_by(2).collect();
+    let mut taken = HashSet::new();
+    let mut count = 0;
+    for &row in &even {
+        for _ in 0..4 {
+            let col = *rng.pick(&even);
+            let through = [('|', col), ('\\', row + side - col), ('/', row + col)];
+            if through.iter().all(|line| !taken.contains(line)) {
+                taken.extend(through);
+                map[row][col] = 'L';
+                count += 1;
+            }
+        }
+    }
+    let input = lines(map.iter().map(|row| row.iter().collect::<String>()));
+    (input, count.to_string(), count.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+    use crate::solver::solver;
+    use crate::y2020::day8;
+
+    // The oracle itself: every day's samples solve to what they were built
+    // to have
+    #[test]
+    fn test_samples_match_solvers() {
+        for day in 1..=11 {
+            let s = solver(2020, day).unwrap();
+            for &(size, seed) in &[(1, 0), (10, 1), (60, 2), (200, 3)] {
+                let sample = gen::sample(2020, day, size, seed).unwrap();
+                for &part in &Part::BOTH {
+                    let expected = sample.answers.get(day, part);
+                    assert!(expected.is_some());
+                    assert_eq!(
+                        s.solve(part, &sample.input).ok().as_deref(),
+                        expected,
+                        "day {} part {} with size {} and seed {}:\n{}",
+                        day,
+                        part,
+                        size,
+                        seed,
+                        sample.input
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_sample() {
+        for day in 1..=11 {
+            let a = gen::sample(2020, day, 50, 9).unwrap();
+            let b = gen::sample(2020, day, 50, 9).unwrap();
+            assert_eq!((&a.input, &a.answers), (&b.input, &b.answers));
+            assert_ne!(a.input, gen::sample(2020, day, 50, 10).unwrap().input);
+        }
+    }
+
+    #[test]
+    fn test_program_has_one_fix() {
+        for seed in 0..20 {
+            let sample = gen::sample(2020, 8, 100, seed).unwrap();
+            let prog = day8::input_generator(&sample.input).unwrap();
+            assert_eq!(day8::repairs(&prog).unwrap().len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_large_samples() {
+        let lines = |day, size| {
+            gen::sample(2020, day, size, 0)
+                .unwrap()
+                .input
+                .lines()
+                .count()
+        };
+        assert_eq!(lines(1, 100_000), 100_000);
+        assert_eq!(lines(5, 5000), 1023);
+        assert_eq!(lines(9, 5000), 5000);
+        assert_eq!(lines(7, 20_000), 20_000);
+        assert!(lines(10, 1000) >= 1000);
+        assert_eq!(lines(11, 300), 300);
+    }
+
+    #[test]
+    fn test_dense_seats() {
+        // The puzzle's example settles to 37 and 26 seats
+        let example: Vec<Vec<char>> =
+            "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\n\
+                                       L.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL"
+                .lines()
+                .map(|line| line.chars().collect())
+                .collect();
+        assert_eq!(settle_seats(&example, 1, 4), Some(37));
+        assert_eq!(settle_seats(&example, 10, 5), Some(26));
+        // Two seats side by side under a crowd of one fill and empty forever
+        assert_eq!(settle_seats(&[vec!['L', 'L']], 1, 1), None);
+
+        let s = solver(2020, 11).unwrap();
+        for seed in 0..4 {
+            let (input, part1, part2) = dense_seats(40, &mut Rng::new(seed));
+            let seats = input.matches('L').count();
+            assert!(part1.parse::<usize>().unwrap() < seats);
+            assert_eq!(s.solve(Part::One, &input).unwrap(), part1);
+            assert_eq!(s.solve(Part::Two, &input).unwrap(), part2);
+        }
+    }
+
+    #[test]
+    fn test_colour_names_are_distinct() {
+        let names: HashSet<String> = (0..50_000).map(colour_name).collect();
+        assert_eq!(names.len(), 50_000);
+        assert!(!names.contains("shiny gold"));
+    }
+}
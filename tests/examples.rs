@@ -0,0 +1,74 @@
+// Every examples/YEAR/dayN/NAME.txt is solved and checked against the answers
+// in examples/YEAR/dayN/NAME.toml, which holds part1 = "..." and/or
+// part2 = "...". build.rs finds the fixtures, so adding a case only means
+// adding the files.
+use aoc2020::answers::Answers;
+use aoc2020::solver::{solver, solvers};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+fn fixture(year: u32, day: u32, name: &str, extension: &str) -> (PathBuf, String) {
+    let path = PathBuf::from(format!(
+        "examples/{}/day{}/{}.{}",
+        year, day, name, extension
+    ));
+    let text = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("could not read {}: {}", path.display(), e));
+    (path, text)
+}
+
+fn check(year: u32, day: u32, name: &str) {
+    let (input_path, input) = fixture(year, day, name, "txt");
+    let (answers_path, answers) = fixture(year, day, name, "toml");
+    let answers = Answers::parse_day(day, &answers)
+        .unwrap_or_else(|e| panic!("{}: {}", answers_path.display(), e));
+    assert!(
+        answers.iter().next().is_some(),
+        "{} has no answers",
+        answers_path.display()
+    );
+
+    let solver = solver(year, day).unwrap_or_else(|| panic!("no solver for {} day {}", year, day));
+    for (_, part, expected) in answers.iter() {
+        let actual = solver
+            .solve(part, &input)
+            .unwrap_or_else(|e| panic!("{} part {} failed:\n{}", input_path.display(), part, e));
+        assert_eq!(actual, expected, "{} part {}", input_path.display(), part);
+    }
+}
+
+macro_rules! example {
+    ($test:ident, $year:expr, $day:expr, $name:expr) => {
+        #[test]
+        fn $test() {
+            check($year, $day, $name);
+        }
+    };
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+
+#[test]
+fn every_day_has_an_example() {
+    for s in solvers() {
+        let dir = format!("examples/{}", s.year());
+        let covered: BTreeSet<u32> = fs::read_dir(&dir)
+            .unwrap_or_else(|e| panic!("could not read {}: {}", dir, e))
+            .filter_map(|e| {
+                e.ok()?
+                    .file_name()
+                    .to_str()?
+                    .strip_prefix("day")?
+                    .parse()
+                    .ok()
+            })
+            .collect();
+        assert!(
+            covered.contains(&s.day()),
+            "{} day {} has no examples",
+            s.year(),
+            s.day()
+        );
+    }
+}